@@ -0,0 +1,313 @@
+//! Control groups of the current process, read from /proc/self/cgroup and the cgroup filesystems on linux.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use disk::{parse_mountinfo, Mount};
+use sysfs;
+use super::Error;
+
+/// Values of the v1 limit files that are this big mean "no limit", the kernel writes the
+/// largest page aligned i64 there.
+const V1_UNLIMITED: u64 = 1 << 62;
+
+/// The version of the cgroup hierarchy the limits are read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CgroupVersion {
+    V1,
+    V2,
+}
+
+/// Resource limits of the cgroups of the current process, such as the limits of a Docker or
+/// Kubernetes container. A None limit means there is no limit, or the controller isn't available.
+///
+/// Limits of the parent groups apply too, so the smallest limit of the group and its parents
+/// (as far as they are visible) is returned.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CgroupLimits {
+    /// V1 if any of the memory, cpu, cpuset or pids controllers is on a cgroup v1 hierarchy.
+    pub version: CgroupVersion,
+    /// Memory limit in bytes.
+    pub memory_limit: Option<u64>,
+    /// Memory usage of the group in bytes, including the page cache.
+    pub memory_usage: Option<u64>,
+    /// The part of the page cache in `memory_usage` that can be dropped without swapping, the
+    /// inactive_file value of memory.stat. Usage minus this is the working set that Docker and
+    /// Kubernetes report.
+    pub memory_reclaimable: Option<u64>,
+    /// Cpu time the group can use in every period, in microseconds.
+    pub cpu_quota: Option<u64>,
+    /// Length of the cpu quota period in microseconds.
+    pub cpu_period: Option<u64>,
+    /// The cpus the group can run on.
+    pub cpuset_cpus: Option<Vec<u32>>,
+    /// Maximum number of processes and threads of the group.
+    pub pids_limit: Option<u64>,
+}
+
+impl CgroupLimits {
+    /// The cpu quota as a count of cpus, such as 1.5 for a quota of 150000 in a period of 100000.
+    pub fn effective_cpus(&self) -> Option<f64> {
+        match (self.cpu_quota, self.cpu_period) {
+            (Some(quota), Some(period)) if period > 0 => Some(quota as f64 / period as f64),
+            _ => None,
+        }
+    }
+}
+
+/// One line of /proc/[pid]/cgroup: the hierarchy id, its controllers and the path of the group.
+/// The cgroup v2 line has the id 0 and no controllers.
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct CgroupEntry {
+    pub(crate) hierarchy: u32,
+    pub(crate) controllers: Vec<String>,
+    pub(crate) path: String,
+}
+
+pub(crate) fn parse_proc_cgroup(s: &str) -> Vec<CgroupEntry> {
+    s.lines().filter_map(|line| {
+        let mut fields = line.splitn(3, ':');
+        let hierarchy = fields.next()?.parse::<u32>().ok()?;
+        let controllers = fields.next()?.split(',').filter(|controller| !controller.is_empty()).map(str::to_string).collect();
+        let path = fields.next()?.to_string();
+
+        Some(CgroupEntry { hierarchy, controllers, path })
+    }).collect()
+}
+
+/// The cgroup and cgroup2 mounts of the current mount namespace.
+pub(crate) fn cgroup_mounts() -> Result<Vec<Mount>, Error> {
+    let mountinfo = fs::read_to_string("/proc/self/mountinfo")?;
+
+    Ok(parse_mountinfo(&mountinfo).into_iter().filter(|mount| mount.fs_type == "cgroup" || mount.fs_type == "cgroup2").collect())
+}
+
+/// Joins the path of a group to the mount point of its hierarchy. Without a cgroup namespace a
+/// container gets its own group mounted as the root of the hierarchy, so the mount root is
+/// stripped from the path.
+pub(crate) fn group_dir(mount: &Mount, path: &str) -> PathBuf {
+    let relative = match path.strip_prefix(mount.root.as_str()) {
+        Some(relative) if mount.root != "/" && (relative.is_empty() || relative.starts_with('/')) => relative,
+        _ => path,
+    };
+
+    PathBuf::from(&mount.mount_point).join(relative.trim_start_matches('/'))
+}
+
+/// Get the directory of a cgroup v2 group. The path is relative to the root of the cgroup v2
+/// hierarchy, like in /proc/self/cgroup. If it's None, the group of the current process is used.
+pub(crate) fn cgroup2_dir(cgroup: Option<&str>) -> Result<PathBuf, Error> {
+    let mount = cgroup_mounts()?.into_iter()
+        .find(|mount| mount.fs_type == "cgroup2")
+        .ok_or_else(|| Error::General("there is no cgroup v2 hierarchy mounted".to_string()))?;
+
+    let path = match cgroup {
+        Some(path) => path.to_string(),
+        None => parse_proc_cgroup(&fs::read_to_string("/proc/self/cgroup")?).into_iter()
+            .find(|entry| entry.hierarchy == 0 && entry.controllers.is_empty())
+            .map(|entry| entry.path)
+            .ok_or_else(|| Error::General("the current process is not in a cgroup v2 group".to_string()))?,
+    };
+
+    Ok(group_dir(&mount, &path))
+}
+
+/// Get the cpu, memory and pids limits of the cgroups of the current process, on both cgroup v1
+/// and v2, only for linux.
+pub fn cgroup_limits() -> Result<CgroupLimits, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    let mounts = cgroup_mounts()?;
+    if mounts.is_empty() {
+        return Err(Error::General("there is no cgroup hierarchy mounted".to_string()));
+    }
+
+    let entries = parse_proc_cgroup(&fs::read_to_string("/proc/self/cgroup")?);
+    let memory = controller_dir(&mounts, &entries, "memory");
+    let cpu = controller_dir(&mounts, &entries, "cpu");
+    let cpuset = controller_dir(&mounts, &entries, "cpuset");
+    let pids = controller_dir(&mounts, &entries, "pids");
+
+    let version = if [&memory, &cpu, &cpuset, &pids].iter().any(|dir| matches!(dir, Some((CgroupVersion::V1, _, _)))) {
+        CgroupVersion::V1
+    } else {
+        CgroupVersion::V2
+    };
+
+    let mut limits = CgroupLimits {
+        version,
+        memory_limit: None,
+        memory_usage: None,
+        memory_reclaimable: None,
+        cpu_quota: None,
+        cpu_period: None,
+        cpuset_cpus: None,
+        pids_limit: None,
+    };
+
+    if let Some((version, dir, root)) = memory {
+        let (limit_file, usage_file, inactive_file_key) = match version {
+            CgroupVersion::V1 => ("memory.limit_in_bytes", "memory.usage_in_bytes", "total_inactive_file"),
+            CgroupVersion::V2 => ("memory.max", "memory.current", "inactive_file"),
+        };
+
+        limits.memory_limit = ancestors(&dir, &root).iter().filter_map(|dir| read_limit(dir.join(limit_file))).min();
+        limits.memory_usage = sysfs::read_u64(dir.join(usage_file));
+        limits.memory_reclaimable = sysfs::read_string(dir.join("memory.stat")).and_then(|stat| {
+            stat.lines().find_map(|line| {
+                let (key, value) = line.split_once(' ')?;
+                if key == inactive_file_key { value.parse::<u64>().ok() } else { None }
+            })
+        });
+    }
+
+    if let Some((version, dir, root)) = cpu {
+        // the smallest quota relative to its period is the one that limits.
+        let quota = ancestors(&dir, &root).iter().filter_map(|dir| match version {
+            CgroupVersion::V1 => Some((read_limit(dir.join("cpu.cfs_quota_us"))?, sysfs::read_u64(dir.join("cpu.cfs_period_us"))?)),
+            CgroupVersion::V2 => parse_cpu_max(&sysfs::read_string(dir.join("cpu.max"))?),
+        }).filter(|(_, period)| *period > 0).min_by(|(quota1, period1), (quota2, period2)| (quota1 * period2).cmp(&(quota2 * period1)));
+
+        if let Some((quota, period)) = quota {
+            limits.cpu_quota = Some(quota);
+            limits.cpu_period = Some(period);
+        }
+    }
+
+    if let Some((version, dir, root)) = cpuset {
+        let files: &[&str] = match version {
+            CgroupVersion::V1 => &["cpuset.effective_cpus", "cpuset.cpus"],
+            CgroupVersion::V2 => &["cpuset.cpus.effective", "cpuset.cpus"],
+        };
+
+        // the nearest group with cpuset files, on v2 the cpuset controller may only be enabled higher up.
+        limits.cpuset_cpus = ancestors(&dir, &root).iter()
+            .flat_map(|dir| files.iter().map(move |file| dir.join(file)))
+            .filter_map(sysfs::read_string)
+            .map(|cpus| sysfs::parse_cpu_list(&cpus))
+            .find(|cpus| !cpus.is_empty());
+    }
+
+    if let Some((_, dir, root)) = pids {
+        limits.pids_limit = ancestors(&dir, &root).iter().filter_map(|dir| read_limit(dir.join("pids.max"))).min();
+    }
+
+    Ok(limits)
+}
+
+/// Finds the directory of the group of the current process for a controller, and the mount point
+/// of its hierarchy. A v1 hierarchy that has the controller is preferred, otherwise the v2
+/// hierarchy is used.
+///
+/// On v2 the directory is returned even if the controller isn't enabled for the group itself,
+/// since the limits of its parents still apply, such as the `CPUQuota=` of a systemd slice whose
+/// services don't get the cpu controller. The limit files are read from every level that has them.
+fn controller_dir(mounts: &[Mount], entries: &[CgroupEntry], controller: &str) -> Option<(CgroupVersion, PathBuf, PathBuf)> {
+    for entry in entries.iter().filter(|entry| entry.controllers.iter().any(|name| name == controller)) {
+        let mount = mounts.iter().find(|mount| mount.fs_type == "cgroup" && mount.super_options.iter().any(|option| option == controller));
+
+        if let Some(mount) = mount {
+            return Some((CgroupVersion::V1, group_dir(mount, &entry.path), PathBuf::from(&mount.mount_point)));
+        }
+    }
+
+    let entry = entries.iter().find(|entry| entry.hierarchy == 0 && entry.controllers.is_empty())?;
+    let mount = mounts.iter().find(|mount| mount.fs_type == "cgroup2")?;
+
+    Some((CgroupVersion::V2, group_dir(mount, &entry.path), PathBuf::from(&mount.mount_point)))
+}
+
+/// The directory and its parents up to the mount point of the hierarchy.
+fn ancestors(dir: &Path, root: &Path) -> Vec<PathBuf> {
+    dir.ancestors().take_while(|dir| dir.starts_with(root)).map(Path::to_path_buf).collect()
+}
+
+/// Reads a limit file, None if it's missing or means there is no limit: "max" on v2, -1 or a
+/// huge value on v1.
+fn read_limit<P: AsRef<Path>>(path: P) -> Option<u64> {
+    let value = sysfs::read_string(path)?.parse::<u64>().ok()?;
+
+    if value >= V1_UNLIMITED { None } else { Some(value) }
+}
+
+/// Parses a cgroup v2 cpu.max file, such as "150000 100000", or "max 100000" if there is no quota.
+fn parse_cpu_max(s: &str) -> Option<(u64, u64)> {
+    let mut fields = s.split_whitespace();
+    let quota = fields.next()?.parse::<u64>().ok()?;
+    let period = fields.next().map(|period| period.parse::<u64>().ok()).unwrap_or(Some(100000))?;
+
+    Some((quota, period))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_parse_proc_cgroup() {
+        let entries = parse_proc_cgroup("12:cpu,cpuacct:/docker/abc\n1:name=systemd:/docker/abc\n0::/user.slice/session-1.scope\n");
+
+        assert_eq!(entries.len(), 3);
+        assert_eq!(entries[0].controllers, vec!["cpu", "cpuacct"]);
+        assert_eq!(entries[0].path, "/docker/abc");
+        assert_eq!(entries[2], CgroupEntry { hierarchy: 0, controllers: vec![], path: "/user.slice/session-1.scope".to_string() });
+    }
+
+    #[test]
+    pub fn test_cgroup_limit_parsers() {
+        assert_eq!(parse_cpu_max("150000 100000"), Some((150000, 100000)));
+        assert_eq!(parse_cpu_max("max 100000"), None);
+        assert_eq!(sysfs::parse_cpu_list("0-3,8,10-11\n"), vec![0, 1, 2, 3, 8, 10, 11]);
+        assert_eq!(sysfs::parse_cpu_list(""), Vec::<u32>::new());
+
+        let limits = CgroupLimits {
+            version: CgroupVersion::V2,
+            memory_limit: None,
+            memory_usage: None,
+            memory_reclaimable: None,
+            cpu_quota: Some(150000),
+            cpu_period: Some(100000),
+            cpuset_cpus: None,
+            pids_limit: None,
+        };
+        assert_eq!(limits.effective_cpus(), Some(1.5));
+    }
+
+    #[test]
+    pub fn test_controller_dir() {
+        let mounts = vec![
+            Mount { fs_type: "cgroup".to_string(), mount_point: "/sys/fs/cgroup/pids".to_string(), root: "/".to_string(), super_options: vec!["rw".to_string(), "pids".to_string()], ..Default::default() },
+            Mount { fs_type: "cgroup2".to_string(), mount_point: "/sys/fs/cgroup/unified".to_string(), root: "/".to_string(), ..Default::default() },
+        ];
+        let entries = parse_proc_cgroup("5:pids:/system.slice/app.service
+0::/system.slice/app.service
+");
+
+        assert_eq!(controller_dir(&mounts, &entries, "pids"), Some((CgroupVersion::V1, PathBuf::from("/sys/fs/cgroup/pids/system.slice/app.service"), PathBuf::from("/sys/fs/cgroup/pids"))));
+        // the cpu controller of a slice with a quota may not be enabled for its services.
+        assert_eq!(controller_dir(&mounts, &entries, "cpu"), Some((CgroupVersion::V2, PathBuf::from("/sys/fs/cgroup/unified/system.slice/app.service"), PathBuf::from("/sys/fs/cgroup/unified"))));
+        assert_eq!(controller_dir(&mounts[..1], &entries, "cpu"), None);
+    }
+
+    #[test]
+    pub fn test_ancestors() {
+        let dirs = ancestors(Path::new("/sys/fs/cgroup/kubepods/pod1/container"), Path::new("/sys/fs/cgroup"));
+
+        assert_eq!(dirs.len(), 4);
+        assert_eq!(dirs[0], PathBuf::from("/sys/fs/cgroup/kubepods/pod1/container"));
+        assert_eq!(dirs[3], PathBuf::from("/sys/fs/cgroup"));
+    }
+
+    #[test]
+    pub fn test_group_dir() {
+        let mut mount = Mount { root: "/".to_string(), mount_point: "/sys/fs/cgroup".to_string(), ..Default::default() };
+        assert_eq!(group_dir(&mount, "/user.slice"), PathBuf::from("/sys/fs/cgroup/user.slice"));
+        assert_eq!(group_dir(&mount, "/"), PathBuf::from("/sys/fs/cgroup"));
+
+        mount.root = "/docker/abc".to_string();
+        assert_eq!(group_dir(&mount, "/docker/abc"), PathBuf::from("/sys/fs/cgroup"));
+        assert_eq!(group_dir(&mount, "/docker/abc/child"), PathBuf::from("/sys/fs/cgroup/child"));
+    }
+}
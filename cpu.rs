@@ -0,0 +1,632 @@
+//! Cpu identification, time accounting, utilization, frequencies and topology, read from /proc and /sys on linux.
+
+use std::collections::BTreeSet;
+use std::fs::File;
+use std::io::Read;
+
+use super::Error;
+use sysfs;
+
+/// Time a cpu spent in each state since boot, in USER_HZ ticks (usually 1/100 second).
+///
+/// See the `/proc/stat` section of `man 5 proc`. Fields that the running kernel doesn't
+/// report are zero.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct CpuTime {
+    pub user: u64,
+    pub nice: u64,
+    pub system: u64,
+    pub idle: u64,
+    pub iowait: u64,
+    pub irq: u64,
+    pub softirq: u64,
+    pub steal: u64,
+    /// Time spent running a virtual cpu for guests, already included in `user`.
+    pub guest: u64,
+    /// Time spent running a niced guest, already included in `nice`.
+    pub guest_nice: u64,
+}
+
+impl CpuTime {
+    /// Time the cpu was doing nothing, including the time it waited for io.
+    pub fn idle_total(&self) -> u64 {
+        self.idle + self.iowait
+    }
+
+    /// Time the cpu was busy with anything.
+    pub fn busy_total(&self) -> u64 {
+        self.user + self.nice + self.system + self.irq + self.softirq + self.steal
+    }
+
+    /// All accounted time. Guest time is not added twice since the kernel counts it in `user` and `nice`.
+    pub fn total(&self) -> u64 {
+        self.idle_total() + self.busy_total()
+    }
+}
+
+/// Cpu time of one logical cpu.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CoreCpuTime {
+    /// Logical cpu number, the "N" of "cpuN" in /proc/stat.
+    pub cpu: u32,
+    pub time: CpuTime,
+}
+
+/// Aggregate and per core cpu times.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuTimes {
+    /// Sum of all cpus, the "cpu" line of /proc/stat.
+    pub total: CpuTime,
+    /// One entry per online logical cpu. Offline cpus are not listed by the kernel.
+    pub per_core: Vec<CoreCpuTime>,
+}
+
+/// Cpu utilization between two `CpuTimes` snapshots, as percentages between 0 and 100.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuUsage {
+    pub total: f64,
+    /// Logical cpu number and its utilization. Cpus that are missing in one of the snapshots are skipped.
+    pub per_core: Vec<(u32, f64)>,
+}
+
+/// Get aggregate and per core cpu times, only for linux.
+pub fn cpu_times() -> Result<CpuTimes, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    let mut s = String::new();
+    File::open("/proc/stat")?.read_to_string(&mut s)?;
+
+    parse_proc_stat(&s)
+}
+
+fn parse_proc_stat(s: &str) -> Result<CpuTimes, Error> {
+    let mut total = None;
+    let mut per_core = vec![];
+
+    for line in s.lines() {
+        let mut fields = line.split_whitespace();
+        let label = match fields.next() {
+            Some(label) if label.starts_with("cpu") => label,
+            _ => continue,
+        };
+
+        let mut values = [0u64; 10];
+        for (value, field) in values.iter_mut().zip(fields) {
+            *value = field.parse::<u64>().map_err(|_| Error::General(format!("invalid value in /proc/stat line: {}", line)))?;
+        }
+
+        let time = CpuTime {
+            user: values[0],
+            nice: values[1],
+            system: values[2],
+            idle: values[3],
+            iowait: values[4],
+            irq: values[5],
+            softirq: values[6],
+            steal: values[7],
+            guest: values[8],
+            guest_nice: values[9],
+        };
+
+        match &label[3..] {
+            "" => total = Some(time),
+            num => match num.parse::<u32>() {
+                Ok(cpu) => per_core.push(CoreCpuTime { cpu, time }),
+                Err(_) => continue,
+            },
+        }
+    }
+
+    match total {
+        Some(total) => Ok(CpuTimes { total, per_core }),
+        None => Err(Error::General("/proc/stat has no aggregate cpu line".to_string())),
+    }
+}
+
+fn usage_percent(prev: &CpuTime, cur: &CpuTime) -> f64 {
+    let total = cur.total().saturating_sub(prev.total());
+    if total == 0 {
+        return 0.0;
+    }
+
+    let busy = cur.busy_total().saturating_sub(prev.busy_total());
+    (busy as f64 / total as f64 * 100.0).min(100.0)
+}
+
+/// Calculates the utilization between two snapshots taken with `cpu_times()`.
+pub fn cpu_usage_between(prev: &CpuTimes, cur: &CpuTimes) -> CpuUsage {
+    let per_core = cur.per_core.iter()
+        .filter_map(|core| {
+            prev.per_core.iter()
+                .find(|old| old.cpu == core.cpu)
+                .map(|old| (core.cpu, usage_percent(&old.time, &core.time)))
+        })
+        .collect();
+
+    CpuUsage {
+        total: usage_percent(&prev.total, &cur.total),
+        per_core,
+    }
+}
+
+/// Keeps the last `cpu_times()` snapshot and reports the utilization since then on every sample.
+///
+/// ```no_run
+/// use sys_info_extended::CpuUsageSampler;
+///
+/// let mut sampler = CpuUsageSampler::new().unwrap();
+/// std::thread::sleep(std::time::Duration::from_secs(1));
+/// let usage = sampler.sample().unwrap();
+/// println!("cpu usage: {:.1}%", usage.total);
+/// ```
+#[derive(Debug, Clone)]
+pub struct CpuUsageSampler {
+    last: CpuTimes,
+}
+
+impl CpuUsageSampler {
+    /// Takes the first snapshot.
+    pub fn new() -> Result<CpuUsageSampler, Error> {
+        Ok(CpuUsageSampler { last: cpu_times()? })
+    }
+
+    /// Takes a new snapshot and returns the utilization since the previous one.
+    pub fn sample(&mut self) -> Result<CpuUsage, Error> {
+        let current = cpu_times()?;
+        let usage = cpu_usage_between(&self.last, &current);
+        self.last = current;
+
+        Ok(usage)
+    }
+
+    /// The snapshot that the next `sample()` call will be compared against.
+    pub fn last(&self) -> &CpuTimes {
+        &self.last
+    }
+}
+
+/// Where the values of a `CpuFrequency` came from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrequencySource {
+    /// /sys/devices/system/cpu/cpuN/cpufreq.
+    Cpufreq,
+    /// The "cpu MHz" or "clock" line of /proc/cpuinfo, only the current frequency is known.
+    CpuInfo,
+}
+
+/// Frequencies of one logical cpu, in MHz. Values that the kernel doesn't expose are `None`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct CpuFrequency {
+    /// Logical cpu number.
+    pub cpu: u32,
+    pub current: Option<u64>,
+    /// Lowest frequency the hardware supports.
+    pub min: Option<u64>,
+    /// Highest frequency the hardware supports, including turbo/boost frequencies.
+    pub max: Option<u64>,
+    /// Guaranteed (non-turbo) frequency, only exposed by some drivers like intel_pstate.
+    pub base: Option<u64>,
+    pub source: FrequencySource,
+}
+
+/// Get the current, minimum, maximum and base frequency of every logical cpu, only for linux.
+///
+/// For each cpu the values are read from /sys/devices/system/cpu/cpuN/cpufreq:
+///
+/// * current: `scaling_cur_freq`, then `cpuinfo_cur_freq`.
+/// * min and max: `cpuinfo_min_freq` and `cpuinfo_max_freq`.
+/// * base: `base_frequency`.
+///
+/// If a cpu has no cpufreq directory (no cpufreq driver, most virtual machines), the current
+/// frequency falls back to the "cpu MHz" (x86) or "clock" (powerpc) line of its /proc/cpuinfo
+/// entry. BogoMIPS values are never used since they aren't a frequency.
+pub fn cpu_frequencies() -> Result<Vec<CpuFrequency>, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    let mut cpuinfo = String::new();
+    if let Ok(mut file) = File::open("/proc/cpuinfo") {
+        file.read_to_string(&mut cpuinfo)?;
+    }
+    let cpuinfo_mhz = parse_cpuinfo_mhz(&cpuinfo);
+
+    let mut frequencies = vec![];
+
+    for (cpu, path) in sysfs::cpu_dirs() {
+        let cpufreq = path.join("cpufreq");
+        let khz_to_mhz = |file: &str| sysfs::read_u64(cpufreq.join(file)).map(|khz| khz / 1000);
+
+        if cpufreq.is_dir() {
+            frequencies.push(CpuFrequency {
+                cpu,
+                current: khz_to_mhz("scaling_cur_freq").or_else(|| khz_to_mhz("cpuinfo_cur_freq")),
+                min: khz_to_mhz("cpuinfo_min_freq"),
+                max: khz_to_mhz("cpuinfo_max_freq"),
+                base: khz_to_mhz("base_frequency"),
+                source: FrequencySource::Cpufreq,
+            });
+        } else if let Some(&(_, mhz)) = cpuinfo_mhz.iter().find(|(processor, _)| *processor == cpu) {
+            frequencies.push(CpuFrequency {
+                cpu,
+                current: Some(mhz),
+                min: None,
+                max: None,
+                base: None,
+                source: FrequencySource::CpuInfo,
+            });
+        }
+    }
+
+    // without sysfs, every processor of /proc/cpuinfo is taken as is.
+    if frequencies.is_empty() {
+        frequencies = cpuinfo_mhz.into_iter()
+            .map(|(cpu, mhz)| CpuFrequency {
+                cpu,
+                current: Some(mhz),
+                min: None,
+                max: None,
+                base: None,
+                source: FrequencySource::CpuInfo,
+            })
+            .collect();
+    }
+
+    Ok(frequencies)
+}
+
+/// Returns the processor number and the "cpu MHz" or "clock" value of every /proc/cpuinfo entry.
+fn parse_cpuinfo_mhz(s: &str) -> Vec<(u32, u64)> {
+    let mut result = vec![];
+    let mut processor = None;
+
+    for line in s.lines() {
+        let mut split_line = line.splitn(2, ':');
+        let key = split_line.next().unwrap_or("").trim();
+        let value = split_line.next().unwrap_or("").trim();
+
+        match key {
+            "processor" => processor = value.parse::<u32>().ok(),
+            "cpu MHz" | "clock" => {
+                let mhz = value.trim_end_matches("MHz").trim().parse::<f64>();
+
+                if let (Some(cpu), Ok(mhz)) = (processor, mhz) {
+                    result.push((cpu, mhz as u64));
+                }
+            },
+            _ => {}
+        }
+    }
+
+    result
+}
+
+/// Position of one logical cpu in the cpu topology.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LogicalCpu {
+    /// Logical cpu number.
+    pub cpu: u32,
+    /// Physical socket, `physical_package_id` in sysfs.
+    pub package: u32,
+    /// Die inside the package, always 0 on kernels and cpus that don't report dies.
+    pub die: u32,
+    /// Core number, only unique inside the same package and die.
+    pub core: u32,
+}
+
+/// Cpu topology: which logical cpus share a core, die or package.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuTopology {
+    /// Every online logical cpu, sorted by cpu number.
+    pub cpus: Vec<LogicalCpu>,
+}
+
+impl CpuTopology {
+    /// Quantity of logical cpus, same as `cpu_num()` when every cpu is online.
+    pub fn logical_cpu_count(&self) -> usize {
+        self.cpus.len()
+    }
+
+    /// Quantity of physical sockets.
+    pub fn package_count(&self) -> usize {
+        self.cpus.iter().map(|cpu| cpu.package).collect::<BTreeSet<u32>>().len()
+    }
+
+    /// Quantity of dies over all packages.
+    pub fn die_count(&self) -> usize {
+        self.cpus.iter().map(|cpu| (cpu.package, cpu.die)).collect::<BTreeSet<(u32, u32)>>().len()
+    }
+
+    /// Quantity of physical cores over all packages, smt siblings are counted once.
+    pub fn physical_core_count(&self) -> usize {
+        self.cpus.iter().map(|cpu| (cpu.package, cpu.die, cpu.core)).collect::<BTreeSet<(u32, u32, u32)>>().len()
+    }
+
+    /// Highest quantity of hardware threads that a core has, 1 if smt is disabled or unsupported.
+    pub fn threads_per_core(&self) -> usize {
+        self.cpus.iter().map(|cpu| self.siblings(cpu.cpu).len()).max().unwrap_or(0)
+    }
+
+    /// Logical cpus that share the same physical core with the given cpu, including itself.
+    pub fn siblings(&self, cpu: u32) -> Vec<u32> {
+        match self.cpus.iter().find(|logical| logical.cpu == cpu) {
+            Some(target) => self.cpus.iter()
+                .filter(|logical| (logical.package, logical.die, logical.core) == (target.package, target.die, target.core))
+                .map(|logical| logical.cpu)
+                .collect(),
+            None => vec![],
+        }
+    }
+
+    /// Logical cpus that are in the given package.
+    pub fn package_cpus(&self, package: u32) -> Vec<u32> {
+        self.cpus.iter().filter(|logical| logical.package == package).map(|logical| logical.cpu).collect()
+    }
+}
+
+/// Get the packages, dies, cores and smt siblings of the cpus from /sys/devices/system/cpu/cpuN/topology, only for linux.
+///
+/// Offline cpus don't have a topology directory and are left out.
+pub fn cpu_topology() -> Result<CpuTopology, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    // some architectures report -1 when the id is unknown, that is taken as 0.
+    let read_id = |path: std::path::PathBuf| sysfs::read_string(path)
+        .and_then(|id| id.parse::<i64>().ok())
+        .map(|id| if id < 0 { 0 } else { id as u32 });
+
+    let mut cpus = vec![];
+
+    for (cpu, path) in sysfs::cpu_dirs() {
+        let topology = path.join("topology");
+
+        let core = match read_id(topology.join("core_id")) {
+            Some(core) => core,
+            None => continue,
+        };
+
+        cpus.push(LogicalCpu {
+            cpu,
+            package: read_id(topology.join("physical_package_id")).unwrap_or(0),
+            die: read_id(topology.join("die_id")).unwrap_or(0),
+            core,
+        });
+    }
+
+    if cpus.is_empty() {
+        return Err(Error::General("no cpu topology found in /sys/devices/system/cpu".to_string()));
+    }
+
+    Ok(CpuTopology { cpus })
+}
+
+/// Identification of the cpu, parsed from /proc/cpuinfo.
+///
+/// x86 cpus fill every field. On ARM, `family` is the "CPU architecture", `model` is the
+/// "CPU part" and `stepping` is the "CPU revision" line, and `vendor_id` is the name of the
+/// "CPU implementer" code. Other architectures usually only have a model name and flags.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct CpuInfo {
+    /// Such as "GenuineIntel", "AuthenticAMD" or "ARM".
+    pub vendor_id: Option<String>,
+    /// Such as "Intel(R) Core(TM) i7-8550U CPU @ 1.80GHz".
+    pub model_name: Option<String>,
+    pub family: Option<u32>,
+    pub model: Option<u32>,
+    pub stepping: Option<u32>,
+    /// Microcode revision as the kernel prints it, such as "0xf4".
+    pub microcode: Option<String>,
+    /// Machine hardware name of the kernel, such as "x86_64" or "aarch64".
+    pub architecture: String,
+    /// The "flags" (x86), "Features" (ARM) or "isa" extensions (RISC-V) of the cpu.
+    pub flags: BTreeSet<String>,
+}
+
+impl CpuInfo {
+    /// Checks if the cpu has the given flag, such as "avx2" or "neon".
+    pub fn has_flag(&self, flag: &str) -> bool {
+        self.flags.contains(flag)
+    }
+}
+
+/// Get the vendor, model name, family, model, stepping, microcode revision, architecture and flags of the cpu, only for linux.
+///
+/// Only the first processor entry of /proc/cpuinfo is used.
+pub fn cpu_info() -> Result<CpuInfo, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    let mut s = String::new();
+    File::open("/proc/cpuinfo")?.read_to_string(&mut s)?;
+
+    Ok(parse_cpuinfo(&s, machine()))
+}
+
+fn parse_cpuinfo(s: &str, architecture: String) -> CpuInfo {
+    let mut info = CpuInfo { architecture, ..Default::default() };
+    let mut processors = 0;
+    let mut hardware = None;
+    let mut implementer = None;
+
+    for line in s.lines() {
+        let mut split_line = line.splitn(2, ':');
+        let key = split_line.next().unwrap_or("").trim();
+        let value = split_line.next().unwrap_or("").trim();
+
+        if key == "processor" {
+            processors += 1;
+        }
+
+        // the entries of the other processors are skipped, but the global lines that some
+        // kernels print after them still count.
+        if processors > 1 && key != "Hardware" {
+            continue;
+        }
+
+        match key {
+            "vendor_id" => info.vendor_id = Some(value.to_string()),
+            "CPU implementer" => implementer = parse_hex_or_dec(value),
+            "model name" | "cpu model" => info.model_name = Some(value.to_string()),
+            "cpu" | "uarch" if info.model_name.is_none() => info.model_name = Some(value.to_string()),
+            "Hardware" => hardware = Some(value.to_string()),
+            "cpu family" | "CPU architecture" => info.family = parse_hex_or_dec(value),
+            "model" | "CPU part" => info.model = parse_hex_or_dec(value),
+            "stepping" | "CPU revision" => info.stepping = parse_hex_or_dec(value),
+            "microcode" => info.microcode = Some(value.to_string()),
+            "flags" | "Features" | "features" => {
+                info.flags = value.split_whitespace().map(|flag| flag.to_string()).collect();
+            },
+            "isa" => {
+                // such as "rv64imafdc_zicsr_zifencei", the single letter extensions come after "rv32"/"rv64".
+                let mut extensions = value.split('_');
+                if let Some(base) = extensions.next() {
+                    let letters = base.trim_start_matches("rv32").trim_start_matches("rv64");
+                    info.flags.extend(letters.chars().map(|c| c.to_string()));
+                }
+                info.flags.extend(extensions.map(|extension| extension.to_string()));
+            },
+            _ => {}
+        }
+    }
+
+    if info.vendor_id.is_none() {
+        info.vendor_id = implementer.map(|code| arm_implementer_name(code).to_string());
+    }
+
+    if info.model_name.is_none() {
+        info.model_name = hardware;
+    }
+
+    info
+}
+
+fn parse_hex_or_dec(value: &str) -> Option<u32> {
+    match value.strip_prefix("0x") {
+        Some(hex) => u32::from_str_radix(hex, 16).ok(),
+        None => value.parse::<u32>().ok(),
+    }
+}
+
+/// Names of the "CPU implementer" codes, from the MIDR_EL1 register documentation of ARM.
+fn arm_implementer_name(code: u32) -> &'static str {
+    match code {
+        0x41 => "ARM",
+        0x42 => "Broadcom",
+        0x43 => "Cavium",
+        0x44 => "DEC",
+        0x46 => "Fujitsu",
+        0x48 => "HiSilicon",
+        0x49 => "Infineon",
+        0x4d => "Motorola",
+        0x4e => "NVIDIA",
+        0x50 => "APM",
+        0x51 => "Qualcomm",
+        0x53 => "Samsung",
+        0x56 => "Marvell",
+        0x61 => "Apple",
+        0x66 => "Faraday",
+        0x69 => "Intel",
+        0x6d => "Microsoft",
+        0x70 => "Phytium",
+        0xc0 => "Ampere",
+        _ => "Unknown",
+    }
+}
+
+#[cfg(unix)]
+fn machine() -> String {
+    unsafe {
+        let mut name: libc::utsname = std::mem::zeroed();
+        if libc::uname(&mut name) == 0 {
+            let cstr = std::ffi::CStr::from_ptr(name.machine.as_ptr());
+            return cstr.to_string_lossy().to_string();
+        }
+    }
+
+    std::env::consts::ARCH.to_string()
+}
+
+#[cfg(not(unix))]
+fn machine() -> String {
+    std::env::consts::ARCH.to_string()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const PROC_STAT: &str = "cpu  200 0 100 600 100 0 0 0 0 0
+cpu0 100 0 50 300 50 0 0 0 0 0
+cpu2 100 0 50 300 50 0 0 0 0 0
+intr 56706 0 0 0
+ctxt 151212
+btime 1792317334
+";
+
+    #[test]
+    pub fn test_parse_proc_stat() {
+        let times = parse_proc_stat(PROC_STAT).unwrap();
+        assert_eq!(times.total.user, 200);
+        assert_eq!(times.total.total(), 1000);
+        assert_eq!(times.per_core.len(), 2);
+        assert_eq!(times.per_core[1].cpu, 2);
+    }
+
+    #[test]
+    pub fn test_cpu_usage_between() {
+        let prev = parse_proc_stat(PROC_STAT).unwrap();
+        let cur = parse_proc_stat("cpu  300 0 200 650 150 0 0 0 0 0
+cpu0 200 0 150 300 50 0 0 0 0 0
+cpu1 0 0 0 0 0 0 0 0 0 0
+cpu2 100 0 50 350 100 0 0 0 0 0
+").unwrap();
+
+        let usage = cpu_usage_between(&prev, &cur);
+        assert_eq!(usage.total, 200.0 / 300.0 * 100.0);
+        assert_eq!(usage.per_core, vec![(0, 100.0), (2, 0.0)]);
+    }
+
+    #[test]
+    pub fn test_parse_cpuinfo_mhz() {
+        let cpuinfo = "processor\t: 0\nvendor_id\t: GenuineIntel\ncpu MHz\t\t: 2000.000\n\nprocessor\t: 1\ncpu MHz\t\t: 3400.512\n\nprocessor\t: 2\nclock\t\t: 3000.000000MHz\n\nprocessor\t: 3\nBogoMIPS\t: 50.00\n";
+        assert_eq!(parse_cpuinfo_mhz(cpuinfo), vec![(0, 2000), (1, 3400), (2, 3000)]);
+    }
+
+    #[test]
+    pub fn test_cpu_topology_counts() {
+        // two packages, each with two cores that have two threads.
+        let topology = CpuTopology {
+            cpus: (0..8).map(|cpu| LogicalCpu { cpu, package: cpu / 4, die: 0, core: (cpu % 4) / 2 }).collect(),
+        };
+
+        assert_eq!(topology.logical_cpu_count(), 8);
+        assert_eq!(topology.package_count(), 2);
+        assert_eq!(topology.die_count(), 2);
+        assert_eq!(topology.physical_core_count(), 4);
+        assert_eq!(topology.threads_per_core(), 2);
+        assert_eq!(topology.siblings(5), vec![4, 5]);
+        assert_eq!(topology.package_cpus(1), vec![4, 5, 6, 7]);
+    }
+
+    #[test]
+    pub fn test_parse_cpuinfo() {
+        let x86 = parse_cpuinfo("processor\t: 0\nvendor_id\t: GenuineIntel\ncpu family\t: 6\nmodel\t\t: 143\nmodel name\t: Intel(R) Xeon(R) Processor\nstepping\t: 8\nmicrocode\t: 0x1\nflags\t\t: fpu sse2 avx2\n\nprocessor\t: 1\nvendor_id\t: Other\n", "x86_64".to_string());
+        assert_eq!(x86.vendor_id.as_deref(), Some("GenuineIntel"));
+        assert_eq!(x86.model_name.as_deref(), Some("Intel(R) Xeon(R) Processor"));
+        assert_eq!((x86.family, x86.model, x86.stepping), (Some(6), Some(143), Some(8)));
+        assert_eq!(x86.microcode.as_deref(), Some("0x1"));
+        assert!(x86.has_flag("avx2"));
+        assert!(!x86.has_flag("avx512f"));
+
+        let arm = parse_cpuinfo("processor\t: 0\nBogoMIPS\t: 108.00\nFeatures\t: fp asimd evtstrm crc32 cpuid\nCPU implementer\t: 0x41\nCPU architecture: 8\nCPU variant\t: 0x0\nCPU part\t: 0xd08\nCPU revision\t: 3\n\nHardware\t: BCM2835\n", "aarch64".to_string());
+        assert_eq!(arm.vendor_id.as_deref(), Some("ARM"));
+        assert_eq!(arm.model_name.as_deref(), Some("BCM2835"));
+        assert_eq!((arm.family, arm.model, arm.stepping), (Some(8), Some(0xd08), Some(3)));
+        assert!(arm.has_flag("asimd"));
+    }
+}
@@ -0,0 +1,391 @@
+//! Mounted filesystems and block device io statistics, read from /proc and statvfs on linux.
+
+use std::collections::HashSet;
+use std::fs::File;
+use std::io::Read;
+use std::time::{Duration, Instant};
+
+use super::Error;
+
+/// One entry of /proc/self/mountinfo with the space and inode usage of the filesystem.
+///
+/// See the `/proc/pid/mountinfo` section of `man 5 proc`. Sizes are in bytes. If the
+/// filesystem can't be queried with statvfs (permission denied, stale network mounts),
+/// the size and inode fields are zero.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Mount {
+    /// Unique id of the mount.
+    pub mount_id: u32,
+    /// Id of the parent mount, the same as `mount_id` for the root of the mount tree.
+    pub parent_id: u32,
+    /// Major device number of the filesystem.
+    pub major: u32,
+    /// Minor device number of the filesystem.
+    pub minor: u32,
+    /// Directory of the filesystem that forms the root of this mount, "/" unless it's a bind mount.
+    pub root: String,
+    /// Mount source, such as "/dev/sda1", "tmpfs" or "server:/export".
+    pub device: String,
+    pub mount_point: String,
+    /// Such as "ext4", "btrfs", "tmpfs" or "nfs4".
+    pub fs_type: String,
+    /// Per mount options, such as "rw" and "relatime".
+    pub options: Vec<String>,
+    /// Per filesystem options.
+    pub super_options: Vec<String>,
+    pub read_only: bool,
+    pub total: u64,
+    /// Free space, including the blocks that are reserved for root.
+    pub free: u64,
+    /// Free space that unprivileged users can use.
+    pub available: u64,
+    pub inodes_total: u64,
+    pub inodes_free: u64,
+}
+
+impl Mount {
+    /// Checks if the filesystem lives on another machine (nfs, smb, fuse sshfs and similar).
+    pub fn is_remote(&self) -> bool {
+        self.device.contains(':')
+            || (self.device.starts_with("//") && (self.fs_type == "smbfs" || self.fs_type == "cifs" || self.fs_type == "smb3"))
+            || self.fs_type.starts_with("nfs")
+            || ["autofs", "gfs", "none", "cifs", "smb3", "9p", "fuse.sshfs"].contains(&self.fs_type.as_str())
+    }
+}
+
+/// Get every mount of the current process' mount namespace, only for linux.
+pub fn mounts() -> Result<Vec<Mount>, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    let mut s = String::new();
+    File::open("/proc/self/mountinfo")?.read_to_string(&mut s)?;
+
+    let mut mounts = parse_mountinfo(&s);
+    for mount in mounts.iter_mut() {
+        fill_usage(mount);
+    }
+
+    Ok(mounts)
+}
+
+/// Get the local, device backed filesystems, only for linux.
+///
+/// Virtual (proc, tmpfs, cgroup...) and remote filesystems are left out, and a device that is
+/// mounted more than once is only listed for its first mount point.
+pub fn filesystems() -> Result<Vec<Mount>, Error> {
+    Ok(local_filesystems(mounts()?, false))
+}
+
+/// The local filesystems that `disk_info()` sums up. Read only mounts are dropped before the
+/// duplicates, like the old c implementation did, so a device that is mounted read only first
+/// and read write later is still counted.
+pub(crate) fn writable_filesystems() -> Result<Vec<Mount>, Error> {
+    Ok(local_filesystems(mounts()?, true))
+}
+
+fn local_filesystems(mounts: Vec<Mount>, writable_only: bool) -> Vec<Mount> {
+    let mut seen = HashSet::new();
+
+    mounts.into_iter()
+        .filter(|mount| !mount.is_remote())
+        .filter(|mount| mount.device.starts_with("/dev/") || mount.device.starts_with("/dev2/"))
+        .filter(|mount| !(writable_only && mount.read_only))
+        .filter(|mount| seen.insert(mount.device.clone()))
+        .collect()
+}
+
+pub(crate) fn parse_mountinfo(s: &str) -> Vec<Mount> {
+    let mut mounts = vec![];
+
+    for line in s.lines() {
+        let fields = line.split(' ').collect::<Vec<&str>>();
+
+        // the optional fields end with a single "-".
+        let separator = match fields.iter().position(|field| *field == "-") {
+            Some(separator) if separator >= 6 && fields.len() >= separator + 3 => separator,
+            _ => continue,
+        };
+
+        let mut device_numbers = fields[2].splitn(2, ':').map(|num| num.parse::<u32>().unwrap_or(0));
+        let options = fields[5].split(',').map(|option| option.to_string()).collect::<Vec<String>>();
+        let super_options = fields.get(separator + 3).map(|options| options.split(',').map(|option| option.to_string()).collect()).unwrap_or_default();
+
+        mounts.push(Mount {
+            mount_id: fields[0].parse().unwrap_or(0),
+            parent_id: fields[1].parse().unwrap_or(0),
+            major: device_numbers.next().unwrap_or(0),
+            minor: device_numbers.next().unwrap_or(0),
+            root: unescape(fields[3]),
+            mount_point: unescape(fields[4]),
+            read_only: options.iter().any(|option| option == "ro"),
+            options,
+            fs_type: unescape(fields[separator + 1]),
+            device: unescape(fields[separator + 2]),
+            super_options,
+            ..Default::default()
+        });
+    }
+
+    mounts
+}
+
+/// The kernel writes spaces, tabs, newlines and backslashes of paths as octal escapes like "\040".
+fn unescape(field: &str) -> String {
+    let bytes = field.as_bytes();
+    let mut result = Vec::with_capacity(bytes.len());
+    let mut i = 0;
+
+    while i < bytes.len() {
+        if bytes[i] == b'\\' && i + 3 < bytes.len() && bytes[i + 1..i + 4].iter().all(|b| (b'0'..=b'7').contains(b)) {
+            let octal = std::str::from_utf8(&bytes[i + 1..i + 4]).unwrap_or("0");
+            result.push(u8::from_str_radix(octal, 8).unwrap_or(b'?'));
+            i += 4;
+        } else {
+            result.push(bytes[i]);
+            i += 1;
+        }
+    }
+
+    String::from_utf8_lossy(&result).into_owned()
+}
+
+#[cfg(unix)]
+fn fill_usage(mount: &mut Mount) {
+    let path = match std::ffi::CString::new(mount.mount_point.as_bytes()) {
+        Ok(path) => path,
+        Err(_) => return,
+    };
+
+    unsafe {
+        let mut svfs: libc::statvfs = std::mem::zeroed();
+        if libc::statvfs(path.as_ptr(), &mut svfs) != 0 {
+            return;
+        }
+
+        let block_size = if svfs.f_frsize > 0 { svfs.f_frsize as u64 } else { svfs.f_bsize as u64 };
+        mount.total = svfs.f_blocks as u64 * block_size;
+        mount.free = svfs.f_bfree as u64 * block_size;
+        mount.available = svfs.f_bavail as u64 * block_size;
+        mount.inodes_total = svfs.f_files as u64;
+        mount.inodes_free = svfs.f_ffree as u64;
+    }
+}
+
+#[cfg(not(unix))]
+fn fill_usage(_mount: &mut Mount) {}
+
+/// Io statistics of one block device since boot, a line of /proc/diskstats.
+///
+/// See the kernel's Documentation/admin-guide/iostats.rst. Times are in milliseconds and a
+/// sector is always 512 bytes there. The discard fields are only reported since linux 4.18
+/// and the flush fields since linux 5.5.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiskIoStats {
+    pub major: u32,
+    pub minor: u32,
+    /// Such as "sda", "sda1" or "nvme0n1".
+    pub name: String,
+    pub reads_completed: u64,
+    pub reads_merged: u64,
+    pub sectors_read: u64,
+    pub read_time_ms: u64,
+    pub writes_completed: u64,
+    pub writes_merged: u64,
+    pub sectors_written: u64,
+    pub write_time_ms: u64,
+    /// Requests that are currently in flight, the only field that is not a counter.
+    pub in_flight: u64,
+    /// Time the device had at least one request in flight.
+    pub io_time_ms: u64,
+    pub weighted_io_time_ms: u64,
+    pub discards_completed: Option<u64>,
+    pub discards_merged: Option<u64>,
+    pub sectors_discarded: Option<u64>,
+    pub discard_time_ms: Option<u64>,
+    pub flushes_completed: Option<u64>,
+    pub flush_time_ms: Option<u64>,
+}
+
+/// Io rates of one block device between two `DiskIoStats` samples.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct DiskIoRate {
+    pub name: String,
+    pub reads_per_sec: f64,
+    pub writes_per_sec: f64,
+    pub read_bytes_per_sec: f64,
+    pub write_bytes_per_sec: f64,
+    /// Average time a completed request took, including the time it waited in the queue.
+    pub avg_wait_ms: f64,
+    /// Percentage of the time the device was busy, near 100 means the device is saturated.
+    pub utilization: f64,
+}
+
+/// Get the io statistics of every block device from /proc/diskstats, only for linux.
+pub fn disk_io_stats() -> Result<Vec<DiskIoStats>, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    let mut s = String::new();
+    File::open("/proc/diskstats")?.read_to_string(&mut s)?;
+
+    Ok(parse_diskstats(&s))
+}
+
+fn parse_diskstats(s: &str) -> Vec<DiskIoStats> {
+    let mut stats = vec![];
+
+    for line in s.lines() {
+        let fields = line.split_whitespace().collect::<Vec<&str>>();
+        if fields.len() < 14 {
+            continue;
+        }
+
+        let values = fields[3..].iter().map(|field| field.parse::<u64>().unwrap_or(0)).collect::<Vec<u64>>();
+        let value = |index: usize| values.get(index).cloned();
+
+        stats.push(DiskIoStats {
+            major: fields[0].parse().unwrap_or(0),
+            minor: fields[1].parse().unwrap_or(0),
+            name: fields[2].to_string(),
+            reads_completed: values[0],
+            reads_merged: values[1],
+            sectors_read: values[2],
+            read_time_ms: values[3],
+            writes_completed: values[4],
+            writes_merged: values[5],
+            sectors_written: values[6],
+            write_time_ms: values[7],
+            in_flight: values[8],
+            io_time_ms: values[9],
+            weighted_io_time_ms: values[10],
+            discards_completed: value(11),
+            discards_merged: value(12),
+            sectors_discarded: value(13),
+            discard_time_ms: value(14),
+            flushes_completed: value(15),
+            flush_time_ms: value(16),
+        });
+    }
+
+    stats
+}
+
+/// Calculates the io rates of the devices that exist in both samples, `elapsed` is the time between them.
+///
+/// A counter that went backwards (the device was removed and added again) counts as zero.
+pub fn disk_io_rates_between(prev: &[DiskIoStats], cur: &[DiskIoStats], elapsed: Duration) -> Vec<DiskIoRate> {
+    let secs = elapsed.as_secs_f64();
+    if secs <= 0.0 {
+        return vec![];
+    }
+
+    cur.iter()
+        .filter_map(|current| {
+            let old = prev.iter().find(|old| old.name == current.name)?;
+
+            let reads = current.reads_completed.saturating_sub(old.reads_completed);
+            let writes = current.writes_completed.saturating_sub(old.writes_completed);
+            let wait_ms = (current.read_time_ms + current.write_time_ms).saturating_sub(old.read_time_ms + old.write_time_ms);
+            let io_ms = current.io_time_ms.saturating_sub(old.io_time_ms);
+
+            Some(DiskIoRate {
+                name: current.name.clone(),
+                reads_per_sec: reads as f64 / secs,
+                writes_per_sec: writes as f64 / secs,
+                read_bytes_per_sec: (current.sectors_read.saturating_sub(old.sectors_read) * 512) as f64 / secs,
+                write_bytes_per_sec: (current.sectors_written.saturating_sub(old.sectors_written) * 512) as f64 / secs,
+                avg_wait_ms: if reads + writes > 0 { wait_ms as f64 / (reads + writes) as f64 } else { 0.0 },
+                utilization: (io_ms as f64 / (secs * 1000.0) * 100.0).min(100.0),
+            })
+        })
+        .collect()
+}
+
+/// Keeps the last `disk_io_stats()` sample and reports the io rates since then on every sample.
+#[derive(Debug, Clone)]
+pub struct DiskIoSampler {
+    last: Vec<DiskIoStats>,
+    taken_at: Instant,
+}
+
+impl DiskIoSampler {
+    /// Takes the first sample.
+    pub fn new() -> Result<DiskIoSampler, Error> {
+        Ok(DiskIoSampler { last: disk_io_stats()?, taken_at: Instant::now() })
+    }
+
+    /// Takes a new sample and returns the io rates since the previous one.
+    pub fn sample(&mut self) -> Result<Vec<DiskIoRate>, Error> {
+        let current = disk_io_stats()?;
+        let now = Instant::now();
+        let rates = disk_io_rates_between(&self.last, &current, now.duration_since(self.taken_at));
+
+        self.last = current;
+        self.taken_at = now;
+
+        Ok(rates)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_parse_mountinfo() {
+        let mountinfo = "23 28 0:22 / /proc rw,relatime - proc proc rw
+36 35 98:0 /mnt1 /mnt/with\\040space ro,noatime master:1 shared:2 - ext3 /dev/root rw,errors=continue
+broken line
+";
+        let mounts = parse_mountinfo(mountinfo);
+        assert_eq!(mounts.len(), 2);
+        assert_eq!(mounts[0].fs_type, "proc");
+        assert!(!mounts[0].read_only);
+
+        let mount = &mounts[1];
+        assert_eq!((mount.mount_id, mount.parent_id, mount.major, mount.minor), (36, 35, 98, 0));
+        assert_eq!(mount.root, "/mnt1");
+        assert_eq!(mount.mount_point, "/mnt/with space");
+        assert_eq!(mount.device, "/dev/root");
+        assert_eq!(mount.fs_type, "ext3");
+        assert_eq!(mount.super_options, vec!["rw", "errors=continue"]);
+        assert!(mount.read_only);
+    }
+
+    #[test]
+    pub fn test_local_filesystems() {
+        let mounts = parse_mountinfo("23 28 0:22 / /proc rw,relatime - proc proc rw
+30 1 8:1 / /snapshot ro,relatime - ext4 /dev/sda1 rw
+31 1 8:1 / /data rw,relatime - ext4 /dev/sda1 rw
+32 1 8:1 /home /home rw,relatime - ext4 /dev/sda1 rw
+33 1 0:50 / /mnt/nfs rw - nfs4 server:/export rw
+");
+
+        let all = local_filesystems(mounts.clone(), false);
+        assert_eq!(all.len(), 1);
+        assert_eq!(all[0].mount_point, "/snapshot");
+
+        let writable = local_filesystems(mounts, true);
+        assert_eq!(writable.len(), 1);
+        assert_eq!(writable[0].mount_point, "/data");
+    }
+
+    #[test]
+    pub fn test_disk_io_rates_between() {
+        let prev = parse_diskstats("   8       0 sda 100 0 800 50 200 0 1600 150 0 100 200\n 259       0 nvme0n1 1 0 8 1 1 0 8 1 0 1 2 0 0 0 0 0 0\n");
+        let cur = parse_diskstats("   8       0 sda 200 0 1600 150 300 0 2400 250 1 600 700\n");
+
+        assert_eq!(prev[0].discards_completed, None);
+        assert_eq!(prev[1].flush_time_ms, Some(0));
+
+        let rates = disk_io_rates_between(&prev, &cur, Duration::from_secs(2));
+        assert_eq!(rates.len(), 1);
+        assert_eq!(rates[0].reads_per_sec, 50.0);
+        assert_eq!(rates[0].write_bytes_per_sec, 800.0 * 512.0 / 2.0);
+        assert_eq!(rates[0].avg_wait_ms, 1.0);
+        assert_eq!(rates[0].utilization, 25.0);
+    }
+}
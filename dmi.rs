@@ -0,0 +1,290 @@
+//! DMI/SMBIOS information of the machine, read from /sys/class/dmi/id on linux.
+
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::Error;
+
+const DMI_ID: &str = "/sys/class/dmi/id";
+
+/// Values that firmware vendors leave in the fields they don't fill, they are returned as None.
+const PLACEHOLDERS: &[&str] = &[
+    "To Be Filled By O.E.M.",
+    "To be filled by O.E.M.",
+    "Default string",
+    "Not Specified",
+    "Not Applicable",
+    "System manufacturer",
+    "System Product Name",
+    "System Version",
+    "System Serial Number",
+    "Base Board Serial Number",
+    "None",
+];
+
+/// The chassis type of SMBIOS, see the "System Enclosure or Chassis" structure (type 3) of the
+/// SMBIOS specification. The same table is used on Windows and Linux.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ChassisType {
+    Other,
+    Unknown,
+    Desktop,
+    LowProfileDesktop,
+    PizzaBox,
+    MiniTower,
+    Tower,
+    Portable,
+    Laptop,
+    Notebook,
+    Handheld,
+    DockingStation,
+    AllInOne,
+    SubNotebook,
+    SpaceSaving,
+    LunchBox,
+    MainSystemChassis,
+    ExpansionChassis,
+    SubChassis,
+    BusExpansionChassis,
+    PeripheralChassis,
+    StorageChassis,
+    RackMountChassis,
+    SealedCasePc,
+    MultiSystemChassis,
+    CompactPci,
+    AdvancedTca,
+    Blade,
+    BladeEnclosure,
+    Tablet,
+    Convertible,
+    Detachable,
+    IotGateway,
+    EmbeddedPc,
+    MiniPc,
+    StickPc,
+}
+
+const CHASSIS_TYPES: [(ChassisType, &str); 36] = [
+    (ChassisType::Other, "Other"),
+    (ChassisType::Unknown, "Unknown"),
+    (ChassisType::Desktop, "Desktop"),
+    (ChassisType::LowProfileDesktop, "Low Profile Desktop"),
+    (ChassisType::PizzaBox, "Pizza Box"),
+    (ChassisType::MiniTower, "Mini Tower"),
+    (ChassisType::Tower, "Tower"),
+    (ChassisType::Portable, "Portable"),
+    (ChassisType::Laptop, "Laptop"),
+    (ChassisType::Notebook, "Notebook"),
+    (ChassisType::Handheld, "Handheld"),
+    (ChassisType::DockingStation, "Docking Station"),
+    (ChassisType::AllInOne, "All-in-One"),
+    (ChassisType::SubNotebook, "Sub-Notebook"),
+    (ChassisType::SpaceSaving, "Space Saving"),
+    (ChassisType::LunchBox, "Lunch Box"),
+    (ChassisType::MainSystemChassis, "Main System Chassis"),
+    (ChassisType::ExpansionChassis, "Expansion Chassis"),
+    (ChassisType::SubChassis, "Sub-Chassis"),
+    (ChassisType::BusExpansionChassis, "Bus Expansion Chassis"),
+    (ChassisType::PeripheralChassis, "Peripheral Chassis"),
+    (ChassisType::StorageChassis, "Storage Chassis"),
+    (ChassisType::RackMountChassis, "Rack Mount Chassis"),
+    (ChassisType::SealedCasePc, "Sealed-Case PC"),
+    (ChassisType::MultiSystemChassis, "Multi-system Chassis"),
+    (ChassisType::CompactPci, "Compact PCI"),
+    (ChassisType::AdvancedTca, "Advanced TCA"),
+    (ChassisType::Blade, "Blade"),
+    (ChassisType::BladeEnclosure, "Blade Enclosure"),
+    (ChassisType::Tablet, "Tablet"),
+    (ChassisType::Convertible, "Convertible"),
+    (ChassisType::Detachable, "Detachable"),
+    (ChassisType::IotGateway, "IoT Gateway"),
+    (ChassisType::EmbeddedPc, "Embedded PC"),
+    (ChassisType::MiniPc, "Mini PC"),
+    (ChassisType::StickPc, "Stick PC"),
+];
+
+impl ChassisType {
+    /// Maps the chassis type number of SMBIOS, 1 for "Other" to 36 for "Stick PC". Bit 7 is the
+    /// chassis lock flag and is ignored, unknown numbers are `Unknown`.
+    pub fn from_smbios(value: u8) -> ChassisType {
+        match (value & 0x7f) as usize {
+            0 => ChassisType::Unknown,
+            number => CHASSIS_TYPES.get(number - 1).map(|(chassis_type, _)| *chassis_type).unwrap_or(ChassisType::Unknown),
+        }
+    }
+
+    /// The name of the chassis type, such as "Rack Mount Chassis".
+    pub fn name(&self) -> &'static str {
+        CHASSIS_TYPES.iter().find(|(chassis_type, _)| chassis_type == self).map(|(_, name)| *name).unwrap_or("Unknown")
+    }
+
+    /// Checks if it's a battery powered, portable computer type.
+    pub fn is_portable(&self) -> bool {
+        matches!(self, ChassisType::Portable | ChassisType::Laptop | ChassisType::Notebook | ChassisType::Handheld
+            | ChassisType::SubNotebook | ChassisType::Tablet | ChassisType::Convertible | ChassisType::Detachable)
+    }
+
+    /// Checks if it's a server chassis type.
+    pub fn is_server(&self) -> bool {
+        matches!(self, ChassisType::MainSystemChassis | ChassisType::RackMountChassis | ChassisType::MultiSystemChassis
+            | ChassisType::Blade | ChassisType::BladeEnclosure)
+    }
+}
+
+impl fmt::Display for ChassisType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The identity of the machine from DMI/SMBIOS: the system, its mainboard and its BIOS.
+///
+/// Fields the firmware doesn't fill (or fills with placeholders such as "To Be Filled By O.E.M.")
+/// are None.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct SystemIdentity {
+    pub manufacturer: Option<String>,
+    pub product_name: Option<String>,
+    pub product_version: Option<String>,
+    /// Only readable by root on linux.
+    pub serial_number: Option<String>,
+    /// Only readable by root on linux.
+    pub uuid: Option<String>,
+    pub sku: Option<String>,
+    pub family: Option<String>,
+    pub board_vendor: Option<String>,
+    pub board_name: Option<String>,
+    pub board_version: Option<String>,
+    /// Only readable by root on linux.
+    pub board_serial_number: Option<String>,
+    pub bios_vendor: Option<String>,
+    pub bios_version: Option<String>,
+    /// As the firmware gives it, usually "MM/DD/YYYY".
+    pub bios_date: Option<String>,
+    pub chassis_type: Option<ChassisType>,
+    /// The fields that exist but couldn't be read because of permissions, such as "serial_number"
+    /// when it's not run as root.
+    pub unreadable: Vec<&'static str>,
+}
+
+/// Get the manufacturer, product, serial number, uuid, board and BIOS of the machine from
+/// /sys/class/dmi/id, only for linux.
+///
+/// The serial numbers and the uuid are only readable by root, without root they are None and
+/// listed in `unreadable` instead of failing the whole call. It's an error if the system has no
+/// DMI information, such as most ARM boards.
+pub fn system_identity() -> Result<SystemIdentity, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    read_system_identity(Path::new(DMI_ID))
+}
+
+fn read_system_identity(dir: &Path) -> Result<SystemIdentity, Error> {
+    if !dir.is_dir() {
+        return Err(Error::General(format!("there is no DMI information in {}", dir.display())));
+    }
+
+    let mut identity = SystemIdentity::default();
+
+    let mut field = |name: &'static str, file: &str| -> Option<String> {
+        match fs::read_to_string(dir.join(file)) {
+            Ok(value) => Some(value.trim().to_string()).filter(|value| !value.is_empty() && !PLACEHOLDERS.contains(&value.as_str())),
+            Err(ref error) if error.kind() == io::ErrorKind::PermissionDenied => {
+                identity.unreadable.push(name);
+                None
+            },
+            Err(_) => None,
+        }
+    };
+
+    let manufacturer = field("manufacturer", "sys_vendor");
+    let product_name = field("product_name", "product_name");
+    let product_version = field("product_version", "product_version");
+    let serial_number = field("serial_number", "product_serial");
+    let uuid = field("uuid", "product_uuid");
+    let sku = field("sku", "product_sku");
+    let family = field("family", "product_family");
+    let board_vendor = field("board_vendor", "board_vendor");
+    let board_name = field("board_name", "board_name");
+    let board_version = field("board_version", "board_version");
+    let board_serial_number = field("board_serial_number", "board_serial");
+    let bios_vendor = field("bios_vendor", "bios_vendor");
+    let bios_version = field("bios_version", "bios_version");
+    let bios_date = field("bios_date", "bios_date");
+    let chassis_type = field("chassis_type", "chassis_type").and_then(|number| number.parse::<u8>().ok()).map(ChassisType::from_smbios);
+
+    Ok(SystemIdentity {
+        manufacturer,
+        product_name,
+        product_version,
+        serial_number,
+        uuid,
+        sku,
+        family,
+        board_vendor,
+        board_name,
+        board_version,
+        board_serial_number,
+        bios_vendor,
+        bios_version,
+        bios_date,
+        chassis_type,
+        ..identity
+    })
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sysfs::TestDir;
+
+    #[test]
+    pub fn test_chassis_type() {
+        assert_eq!(ChassisType::from_smbios(3), ChassisType::Desktop);
+        assert_eq!(ChassisType::from_smbios(10), ChassisType::Notebook);
+        assert_eq!(ChassisType::from_smbios(23), ChassisType::RackMountChassis);
+        assert_eq!(ChassisType::from_smbios(0x80 | 17), ChassisType::MainSystemChassis);
+        assert_eq!(ChassisType::from_smbios(36), ChassisType::StickPc);
+        assert_eq!(ChassisType::from_smbios(0), ChassisType::Unknown);
+        assert_eq!(ChassisType::from_smbios(99), ChassisType::Unknown);
+
+        assert_eq!(ChassisType::RackMountChassis.to_string(), "Rack Mount Chassis");
+        assert!(ChassisType::from_smbios(9).is_portable());
+        assert!(ChassisType::from_smbios(23).is_server());
+        assert!(!ChassisType::Tower.is_server());
+    }
+
+    #[test]
+    pub fn test_read_system_identity() {
+        let dir = TestDir::new("dmi");
+        dir.write(&[
+            ("sys_vendor", "LENOVO"),
+            ("product_name", "20XW0026GE"),
+            ("product_version", "ThinkPad X1 Carbon Gen 9"),
+            ("board_vendor", "LENOVO"),
+            ("board_serial", "To Be Filled By O.E.M."),
+            ("bios_vendor", "LENOVO"),
+            ("bios_version", "N32ET86W (1.62 )"),
+            ("bios_date", "04/11/2023"),
+            ("chassis_type", "10"),
+            ("product_sku", ""),
+        ]);
+
+        let identity = read_system_identity(dir.path()).unwrap();
+
+        assert_eq!(identity.manufacturer.as_deref(), Some("LENOVO"));
+        assert_eq!(identity.product_version.as_deref(), Some("ThinkPad X1 Carbon Gen 9"));
+        assert_eq!(identity.bios_version.as_deref(), Some("N32ET86W (1.62 )"));
+        assert_eq!(identity.bios_date.as_deref(), Some("04/11/2023"));
+        assert_eq!(identity.chassis_type, Some(ChassisType::Notebook));
+        assert_eq!(identity.board_serial_number, None);
+        assert_eq!(identity.sku, None);
+        assert_eq!(identity.serial_number, None);
+
+        assert!(read_system_identity(Path::new("/surely/not/a/dmi/dir")).is_err());
+    }
+}
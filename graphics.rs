@@ -0,0 +1,230 @@
+//! Graphics adapters, the display controllers of the PCI bus on linux and the video controllers
+//! of WMI on windows.
+
+use std::path::Path;
+
+use ids::IdsDatabase;
+use pci::{self, PciDevice};
+use sysfs;
+use super::Error;
+
+/// The PCI base class of display controllers.
+const DISPLAY_CONTROLLER: u8 = 0x03;
+
+/// A graphics adapter. Everything that the system doesn't report is None.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct GraphicsAdapter {
+    /// The model, such as "TU106 [GeForce RTX 2060 Rev. A]". On linux it's only known if a
+    /// pci.ids database is installed.
+    pub name: Option<String>,
+    pub vendor: Option<String>,
+    pub vendor_id: Option<u16>,
+    pub device_id: Option<u16>,
+    /// The PCI address on linux, such as "0000:01:00.0".
+    pub pci_address: Option<String>,
+    /// The kernel driver on linux, such as "amdgpu", "i915" or "nvidia".
+    pub driver: Option<String>,
+    /// Only out of tree linux drivers such as nvidia report their version.
+    pub driver_version: Option<String>,
+    /// Dedicated video memory in bytes. On linux only amdgpu reports it, windows reports at
+    /// most 4 GiB.
+    pub vram_total: Option<u64>,
+    /// Used video memory in bytes, only reported by amdgpu.
+    pub vram_used: Option<u64>,
+    /// Whether the firmware used this adapter as the primary display on boot, only on linux.
+    pub boot_vga: bool,
+    /// The DRM primary node, such as "/dev/dri/card0", only on linux.
+    pub drm_card: Option<String>,
+    /// The DRM render node, such as "/dev/dri/renderD128", only on linux.
+    pub drm_render_node: Option<String>,
+}
+
+/// Get every graphics adapter with its name, vendor, driver and video memory, for linux and
+/// windows.
+///
+/// On linux these are the display controllers (PCI class 0x03) of /sys/bus/pci/devices with
+/// their DRM nodes, the video memory of amdgpu and the names of the pci.ids database. On
+/// windows they are the video controllers of `wmic path win32_VideoController`.
+pub fn graphics_adapters() -> Result<Vec<GraphicsAdapter>, Error> {
+    if cfg!(target_os = "linux") {
+        let database = IdsDatabase::load(&pci::PCI_IDS);
+        return read_graphics_adapters(Path::new("/sys/bus/pci/devices"), Path::new("/sys/module"), database.as_ref());
+    }
+
+    if cfg!(target_os = "windows") {
+        let output = std::process::Command::new("wmic")
+            .args(["path", "win32_VideoController", "get", "Name,AdapterCompatibility,DriverVersion,AdapterRAM,PNPDeviceID", "/format:list"])
+            .output()
+            .map_err(Error::ExecFailed)?;
+
+        return Ok(parse_wmic_video_controllers(&String::from_utf8_lossy(&output.stdout)));
+    }
+
+    Err(Error::UnsupportedSystem)
+}
+
+fn read_graphics_adapters(devices_dir: &Path, modules_dir: &Path, database: Option<&IdsDatabase>) -> Result<Vec<GraphicsAdapter>, Error> {
+    Ok(pci::read_pci_devices(devices_dir, database)?.into_iter()
+        .filter(|device| device.class == DISPLAY_CONTROLLER)
+        .map(|device| {
+            let dir = devices_dir.join(&device.address);
+            read_graphics_adapter(device, &dir, modules_dir)
+        })
+        .collect())
+}
+
+/// Adds the DRM and driver information of the device directory to a PCI display controller.
+fn read_graphics_adapter(device: PciDevice, dir: &Path, modules_dir: &Path) -> GraphicsAdapter {
+    let mut drm_card = None;
+    let mut drm_render_node = None;
+
+    if let Ok(entries) = dir.join("drm").read_dir() {
+        let mut names = entries.flatten().map(|entry| entry.file_name().to_string_lossy().into_owned()).collect::<Vec<String>>();
+        names.sort();
+
+        for name in names {
+            if name.starts_with("renderD") {
+                drm_render_node.get_or_insert(format!("/dev/dri/{}", name));
+            } else if name.strip_prefix("card").is_some_and(|number| number.parse::<u32>().is_ok()) {
+                drm_card.get_or_insert(format!("/dev/dri/{}", name));
+            }
+        }
+    }
+
+    let driver_version = device.driver.as_ref().and_then(|driver| sysfs::read_string(modules_dir.join(driver).join("version")));
+
+    GraphicsAdapter {
+        name: device.device_name,
+        vendor: device.vendor_name,
+        vendor_id: Some(device.vendor_id),
+        device_id: Some(device.device_id),
+        pci_address: Some(device.address),
+        driver: device.driver,
+        driver_version,
+        vram_total: sysfs::read_u64(dir.join("mem_info_vram_total")),
+        vram_used: sysfs::read_u64(dir.join("mem_info_vram_used")),
+        boot_vga: sysfs::read_string(dir.join("boot_vga")).as_deref() == Some("1"),
+        drm_card,
+        drm_render_node,
+    }
+}
+
+/// Parses the "Key=Value" blocks of `wmic ... /format:list`, one block per video controller.
+/// The ids are read from the PNP device id, such as "PCI\VEN_10DE&DEV_1F08&SUBSYS_...".
+fn parse_wmic_video_controllers(output: &str) -> Vec<GraphicsAdapter> {
+    let mut adapters = vec![];
+    let mut adapter: Option<GraphicsAdapter> = None;
+
+    for line in output.lines().map(|line| line.trim()) {
+        let (key, value) = match line.split_once('=') {
+            Some(entry) => entry,
+            None => {
+                adapters.extend(adapter.take());
+                continue;
+            },
+        };
+
+        let adapter = adapter.get_or_insert_with(GraphicsAdapter::default);
+        let value = Some(value.trim().to_string()).filter(|value| !value.is_empty());
+
+        match key {
+            "Name" => adapter.name = value,
+            "AdapterCompatibility" => adapter.vendor = value,
+            "DriverVersion" => adapter.driver_version = value,
+            "AdapterRAM" => adapter.vram_total = value.and_then(|ram| ram.parse::<u64>().ok()).filter(|ram| *ram > 0),
+            "PNPDeviceID" => {
+                let id = |prefix: &str| value.as_ref()?.split(['\\', '&']).find_map(|part| part.strip_prefix(prefix)).and_then(|id| u16::from_str_radix(id, 16).ok());
+                adapter.vendor_id = id("VEN_");
+                adapter.device_id = id("DEV_");
+            },
+            _ => (),
+        }
+    }
+
+    adapters.extend(adapter);
+    adapters
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sysfs::TestDir;
+
+    // the test links the driver of the device like sysfs does, with unix symlinks.
+    #[cfg(unix)]
+    #[test]
+    pub fn test_read_graphics_adapters() {
+        let dir = TestDir::new("graphics");
+        dir.write(&[
+            ("devices/0000:03:00.0/class", "0x030000"),
+            ("devices/0000:03:00.0/vendor", "0x1002"),
+            ("devices/0000:03:00.0/device", "0x73df"),
+            ("devices/0000:03:00.0/boot_vga", "0"),
+            ("devices/0000:03:00.0/mem_info_vram_total", "12868124672"),
+            ("devices/0000:03:00.0/mem_info_vram_used", "1073741824"),
+            ("devices/0000:00:02.0/class", "0x030000"),
+            ("devices/0000:00:02.0/vendor", "0x8086"),
+            ("devices/0000:00:02.0/device", "0x9a49"),
+            ("devices/0000:00:02.0/boot_vga", "1"),
+            ("devices/0000:03:00.1/class", "0x040300"),
+            ("devices/0000:03:00.1/vendor", "0x1002"),
+            ("devices/0000:03:00.1/device", "0xab28"),
+            ("module/amdgpu/version", "6.2.4"),
+        ]);
+        dir.create_dirs(&[
+            "devices/0000:03:00.0/drm/card1",
+            "devices/0000:03:00.0/drm/renderD129",
+            "devices/0000:00:02.0/drm/card0",
+            "devices/0000:00:02.0/drm/card0-eDP-1",
+            "devices/0000:00:02.0/drm/renderD128",
+            "drivers/amdgpu",
+        ]);
+        dir.symlink("../../drivers/amdgpu", "devices/0000:03:00.0/driver");
+
+        let database = IdsDatabase::parse("\
+1002  Advanced Micro Devices, Inc. [AMD/ATI]
+\t73df  Navi 22 [Radeon RX 6700/6700 XT/6750 XT / 6800M/6850M XT]
+");
+
+        let adapters = read_graphics_adapters(&dir.join("devices"), &dir.join("module"), Some(&database)).unwrap();
+
+        assert_eq!(adapters.len(), 2);
+        assert_eq!(adapters[0].pci_address.as_deref(), Some("0000:00:02.0"));
+        assert!(adapters[0].boot_vga);
+        assert_eq!((adapters[0].name.as_deref(), adapters[0].vendor_id), (None, Some(0x8086)));
+        assert_eq!(adapters[0].drm_card.as_deref(), Some("/dev/dri/card0"));
+        assert_eq!(adapters[0].drm_render_node.as_deref(), Some("/dev/dri/renderD128"));
+        assert_eq!((adapters[0].vram_total, adapters[0].driver.as_deref()), (None, None));
+
+        assert_eq!(adapters[1], GraphicsAdapter {
+            name: Some("Navi 22 [Radeon RX 6700/6700 XT/6750 XT / 6800M/6850M XT]".to_string()),
+            vendor: Some("Advanced Micro Devices, Inc. [AMD/ATI]".to_string()),
+            vendor_id: Some(0x1002),
+            device_id: Some(0x73df),
+            pci_address: Some("0000:03:00.0".to_string()),
+            driver: Some("amdgpu".to_string()),
+            driver_version: Some("6.2.4".to_string()),
+            vram_total: Some(12868124672),
+            vram_used: Some(1073741824),
+            boot_vga: false,
+            drm_card: Some("/dev/dri/card1".to_string()),
+            drm_render_node: Some("/dev/dri/renderD129".to_string()),
+        });
+    }
+
+    #[test]
+    pub fn test_parse_wmic_video_controllers() {
+        let adapters = parse_wmic_video_controllers("\r\r\n\r\r\nAdapterCompatibility=NVIDIA\r\r\nAdapterRAM=4293918720\r\r\nDriverVersion=31.0.15.3179\r\r\nName=NVIDIA GeForce GTX 1650\r\r\nPNPDeviceID=PCI\\VEN_10DE&DEV_1F82&SUBSYS_86AC1043&REV_A1\\4&2A1C4F1B&0&0008\r\r\n\r\r\n\r\r\nAdapterCompatibility=Microsoft Corporation\r\r\nAdapterRAM=\r\r\nDriverVersion=10.0.19041.3636\r\r\nName=Microsoft Basic Display Adapter\r\r\nPNPDeviceID=ROOT\\BASICDISPLAY\\0000\r\r\n\r\r\n");
+
+        assert_eq!(adapters.len(), 2);
+        assert_eq!(adapters[0].name.as_deref(), Some("NVIDIA GeForce GTX 1650"));
+        assert_eq!(adapters[0].vendor.as_deref(), Some("NVIDIA"));
+        assert_eq!((adapters[0].vendor_id, adapters[0].device_id), (Some(0x10de), Some(0x1f82)));
+        assert_eq!(adapters[0].vram_total, Some(4293918720));
+        assert_eq!(adapters[0].driver_version.as_deref(), Some("31.0.15.3179"));
+
+        assert_eq!(adapters[1].vendor_id, None);
+        assert_eq!(adapters[1].vram_total, None);
+        assert!(!adapters[1].boot_vga);
+    }
+}
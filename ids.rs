@@ -0,0 +1,194 @@
+//! A parser of the pci.ids and usb.ids databases that map vendor, device and class ids to names.
+
+use std::collections::HashMap;
+use std::fs;
+
+/// The vendors and classes of a pci.ids or usb.ids file, see https://pci-ids.ucw.cz.
+///
+/// Both files list vendors as "vvvv  name" lines with tab indented "dddd  name" devices, which
+/// are followed by double tab indented "ssss dddd  name" subsystems in pci.ids. Classes are
+/// "C cc  name" lines with subclasses and prog-if/protocol lines under them. The other sections
+/// of usb.ids (HID usages, languages...) are skipped.
+#[derive(Debug, Default)]
+pub(crate) struct IdsDatabase {
+    vendors: HashMap<u16, IdsVendor>,
+    classes: HashMap<u8, IdsClass>,
+}
+
+#[derive(Debug, Default)]
+struct IdsVendor {
+    name: String,
+    devices: HashMap<u16, IdsDevice>,
+}
+
+#[derive(Debug, Default)]
+struct IdsDevice {
+    name: String,
+    subsystems: HashMap<(u16, u16), String>,
+}
+
+#[derive(Debug, Default)]
+struct IdsClass {
+    name: String,
+    subclasses: HashMap<u8, IdsSubclass>,
+}
+
+#[derive(Debug, Default)]
+struct IdsSubclass {
+    name: String,
+    interfaces: HashMap<u8, String>,
+}
+
+/// Splits "id  name" at the first space.
+fn split_id(line: &str) -> Option<(&str, &str)> {
+    let (id, name) = line.split_once(' ')?;
+    Some((id, name.trim()))
+}
+
+fn parse_u16(id: &str) -> Option<u16> {
+    if id.len() == 4 { u16::from_str_radix(id, 16).ok() } else { None }
+}
+
+fn parse_u8(id: &str) -> Option<u8> {
+    if id.len() == 2 { u8::from_str_radix(id, 16).ok() } else { None }
+}
+
+impl IdsDatabase {
+    /// Loads the first of the given files that can be read, None if there is none.
+    pub(crate) fn load(paths: &[&str]) -> Option<IdsDatabase> {
+        paths.iter().find_map(|path| fs::read(path).ok()).map(|content| IdsDatabase::parse(&String::from_utf8_lossy(&content)))
+    }
+
+    pub(crate) fn parse(content: &str) -> IdsDatabase {
+        let mut database = IdsDatabase::default();
+        // the vendor or class of the last top level line and the device or subclass under it.
+        let mut vendor = None;
+        let mut device = None;
+        let mut class = None;
+        let mut subclass = None;
+
+        for line in content.lines() {
+            if line.trim().is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let depth = line.len() - line.trim_start_matches('\t').len();
+            let (id, name) = match split_id(line.trim_start_matches('\t')) {
+                Some(entry) => entry,
+                None => continue,
+            };
+
+            match depth {
+                0 => {
+                    vendor = None;
+                    device = None;
+                    class = None;
+                    subclass = None;
+
+                    if id == "C" {
+                        if let Some((id, name)) = split_id(name) {
+                            class = parse_u8(id);
+                            if let Some(class) = class {
+                                database.classes.insert(class, IdsClass { name: name.to_string(), ..IdsClass::default() });
+                            }
+                        }
+                    } else if let Some(id) = parse_u16(id) {
+                        vendor = Some(id);
+                        database.vendors.insert(id, IdsVendor { name: name.to_string(), ..IdsVendor::default() });
+                    }
+                },
+                1 => {
+                    if let Some(vendor) = vendor.and_then(|vendor| database.vendors.get_mut(&vendor)) {
+                        device = parse_u16(id);
+                        if let Some(device) = device {
+                            vendor.devices.insert(device, IdsDevice { name: name.to_string(), ..IdsDevice::default() });
+                        }
+                    } else if let Some(class) = class.and_then(|class| database.classes.get_mut(&class)) {
+                        subclass = parse_u8(id);
+                        if let Some(subclass) = subclass {
+                            class.subclasses.insert(subclass, IdsSubclass { name: name.to_string(), ..IdsSubclass::default() });
+                        }
+                    }
+                },
+                _ => {
+                    if let (Some(vendor), Some(device)) = (vendor, device) {
+                        let device = database.vendors.get_mut(&vendor).and_then(|vendor| vendor.devices.get_mut(&device));
+                        let subsystem = split_id(name).and_then(|(subdevice, name)| Some(((parse_u16(id)?, parse_u16(subdevice)?), name)));
+
+                        if let (Some(device), Some((ids, name))) = (device, subsystem) {
+                            device.subsystems.insert(ids, name.to_string());
+                        }
+                    } else if let (Some(class), Some(subclass)) = (class, subclass) {
+                        let subclass = database.classes.get_mut(&class).and_then(|class| class.subclasses.get_mut(&subclass));
+
+                        if let (Some(subclass), Some(interface)) = (subclass, parse_u8(id)) {
+                            subclass.interfaces.insert(interface, name.to_string());
+                        }
+                    }
+                },
+            }
+        }
+
+        database
+    }
+
+    pub(crate) fn vendor(&self, vendor: u16) -> Option<&str> {
+        self.vendors.get(&vendor).map(|vendor| vendor.name.as_str())
+    }
+
+    pub(crate) fn device(&self, vendor: u16, device: u16) -> Option<&str> {
+        self.vendors.get(&vendor)?.devices.get(&device).map(|device| device.name.as_str())
+    }
+
+    pub(crate) fn subsystem(&self, vendor: u16, device: u16, subsystem_vendor: u16, subsystem_device: u16) -> Option<&str> {
+        self.vendors.get(&vendor)?.devices.get(&device)?.subsystems.get(&(subsystem_vendor, subsystem_device)).map(|name| name.as_str())
+    }
+
+    /// The most specific name of a class, subclass and prog-if (or protocol) that the database has.
+    pub(crate) fn class(&self, class: u8, subclass: u8, interface: u8) -> Option<&str> {
+        let class = self.classes.get(&class)?;
+
+        match class.subclasses.get(&subclass) {
+            Some(subclass) => Some(subclass.interfaces.get(&interface).unwrap_or(&subclass.name).as_str()),
+            None => Some(class.name.as_str()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_parse_ids_database() {
+        let database = IdsDatabase::parse("\
+# comment
+8086  Intel Corporation
+\t1237  440FX - 82441FX PMC [Natoma]
+\t100e  82540EM Gigabit Ethernet Controller
+\t\t1028 002e  Optiplex GX260
+1af4  Red Hat, Inc.
+\t1041  Virtio 1.0 network device
+
+# List of known device classes
+C 01  Mass storage controller
+\t08  Non-Volatile memory controller
+\t\t02  NVM Express
+C 03  Display controller
+\t00  VGA compatible controller
+HID 00  None
+\t01  Ignored
+");
+
+        assert_eq!(database.vendor(0x8086), Some("Intel Corporation"));
+        assert_eq!(database.device(0x8086, 0x100e), Some("82540EM Gigabit Ethernet Controller"));
+        assert_eq!(database.subsystem(0x8086, 0x100e, 0x1028, 0x002e), Some("Optiplex GX260"));
+        assert_eq!(database.device(0x1af4, 0x1041), Some("Virtio 1.0 network device"));
+        assert_eq!(database.device(0x1af4, 0x1237), None);
+
+        assert_eq!(database.class(0x01, 0x08, 0x02), Some("NVM Express"));
+        assert_eq!(database.class(0x03, 0x00, 0x01), Some("VGA compatible controller"));
+        assert_eq!(database.class(0x03, 0x80, 0x00), Some("Display controller"));
+        assert_eq!(database.class(0x0c, 0x03, 0x30), None);
+    }
+}
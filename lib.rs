@@ -34,6 +34,78 @@ use std::collections::HashMap;
 #[cfg(any(target_os = "solaris", target_os = "illumos"))]
 mod kstat;
 
+mod sysfs;
+mod cpu;
+
+pub use cpu::{cpu_times, cpu_usage_between, CoreCpuTime, CpuTime, CpuTimes, CpuUsage, CpuUsageSampler};
+pub use cpu::{cpu_frequencies, CpuFrequency, FrequencySource};
+pub use cpu::{cpu_topology, CpuTopology, LogicalCpu};
+pub use cpu::{cpu_info, CpuInfo};
+
+mod disk;
+
+pub use disk::{filesystems, mounts, Mount};
+pub use disk::{disk_io_rates_between, disk_io_stats, DiskIoRate, DiskIoSampler, DiskIoStats};
+
+mod net;
+
+pub use net::{network_interfaces, InterfaceAddress, InterfaceFlags, NetworkInterface};
+pub use net::{network_io_rates_between, network_io_stats, NetworkIoRate, NetworkIoSampler, NetworkIoStats};
+
+mod process;
+
+pub use process::{processes, ProcessInfo, ProcessState, Processes};
+pub use process::{process, FdTarget, FileDescriptor, ProcessDetails, ResourceLimit};
+pub use process::{process_tree, ProcessTree, SubtreeUsage};
+
+mod program;
+
+pub use program::{find_executable, which};
+pub use program::{program_version, program_version_with_timeout, Version};
+
+mod package;
+
+pub use package::{installed_packages, Package, PackageManager};
+
+mod cgroup;
+mod pressure;
+
+pub use pressure::{cgroup_pressure, pressure, Pressure, PressureValues, SystemPressure};
+pub use cgroup::{cgroup_limits, CgroupLimits, CgroupVersion};
+
+mod virt;
+
+pub use virt::{virtualization, Container, Hypervisor, Virtualization};
+
+mod sensors;
+
+pub use sensors::{sensors, SensorKind, SensorReading, Sensors, ThermalZone, TripPoint};
+
+mod power;
+
+pub use power::{power_supplies, PowerSupply, PowerSupplyStatus, PowerSupplyType};
+
+mod dmi;
+
+pub use dmi::{system_identity, ChassisType, SystemIdentity};
+
+mod smbios;
+
+pub use smbios::{memory_devices, MemoryDevice, MemoryFormFactor, MemoryType};
+
+mod ids;
+mod pci;
+
+pub use pci::{pci_devices, PciDevice};
+
+mod graphics;
+
+pub use graphics::{graphics_adapters, GraphicsAdapter};
+
+mod usb;
+
+pub use usb::{usb_devices, UsbDevice, UsbInterface};
+
 #[cfg(any(target_vendor = "apple", target_os="freebsd", target_os = "openbsd", target_os = "netbsd"))]
 static OS_CTL_KERN: libc::c_int = 1;
 #[cfg(any(target_vendor = "apple", target_os="freebsd", target_os = "openbsd", target_os = "netbsd"))]
@@ -377,7 +449,7 @@ extern "C" {
     #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
     fn get_mem_info_bsd(mi: &mut MemInfo) ->i32;
 
-    #[cfg(any(target_os = "linux", target_vendor = "apple", target_os = "windows", target_os = "haiku"))]
+    #[cfg(any(target_vendor = "apple", target_os = "windows", target_os = "haiku"))]
     fn get_disk_info() -> DiskInfo;
     #[cfg(any(target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
     fn get_disk_info_bsd(di: &mut DiskInfo) -> i32;
@@ -544,7 +616,7 @@ fn parse_line_for_linux_os_release(l: String) -> Option<(String, String)> {
 
 /// Get cpu num quantity.
 ///
-/// Notice, it returns the logical cpu quantity.
+/// Notice, it returns the logical cpu quantity. Use `cpu_topology()` for physical cores on linux.
 pub fn cpu_num() -> Result<u32, Error> {
     #[cfg(any(target_os = "solaris", target_os = "illumos", target_os = "freebsd", target_os = "openbsd", target_os = "netbsd"))]
     {
@@ -565,9 +637,29 @@ pub fn cpu_num() -> Result<u32, Error> {
     }
 }
 
+/// Get the count of cpus the current process can use. On linux it's `cpu_num()` limited by the
+/// cpu quota (rounded up) and the cpuset of the cgroups of the process, such as 2 in a container
+/// that is limited to 1.5 cpus. On other systems it's the same as `cpu_num()`.
+pub fn effective_cpu_num() -> Result<u32, Error> {
+    let mut num = cpu_num()?;
+
+    if let Ok(limits) = cgroup_limits() {
+        if let Some(cpus) = limits.effective_cpus() {
+            num = num.min((cpus.ceil() as u32).max(1));
+        }
+
+        if let Some(cpuset) = limits.cpuset_cpus.filter(|cpuset| !cpuset.is_empty()) {
+            num = num.min(cpuset.len() as u32);
+        }
+    }
+
+    Ok(num)
+}
+
 /// Get cpu speed.
 ///
-/// Such as 2500, that is 2500 MHz.
+/// Such as 2500, that is 2500 MHz. On linux it's the current frequency of the first cpu, use
+/// `cpu_frequencies()` for all of them.
 pub fn cpu_speed() -> Result<u64, Error> {
     #[cfg(any(target_os = "solaris", target_os = "illumos"))]
     {
@@ -575,20 +667,9 @@ pub fn cpu_speed() -> Result<u64, Error> {
     }
     #[cfg(target_os = "linux")]
     {
-        // /sys/devices/system/cpu/cpu0/cpufreq/cpuinfo_cur_freq
-        let mut s = String::new();
-        File::open("/proc/cpuinfo")?.read_to_string(&mut s)?;
-
-        let find_cpu_mhz = s.split('\n').find(|line|
-            line.starts_with("cpu MHz\t") ||
-                line.starts_with("BogoMIPS") ||
-                line.starts_with("clock\t") ||
-                line.starts_with("bogomips per cpu")
-        );
-
-        find_cpu_mhz.and_then(|line| line.split(':').last())
-            .and_then(|val| val.replace("MHz", "").trim().parse::<f64>().ok())
-            .map(|speed| speed as u64)
+        // see `cpu_frequencies()` for where the values come from.
+        cpu_frequencies()?.iter()
+            .find_map(|frequency| frequency.current.or(frequency.max))
             .ok_or(Error::Unknown)
     }
     #[cfg(any(all(target_vendor = "apple", not(any(target_arch = "aarch64", target_arch = "arm"))), target_os = "windows", target_os = "haiku"))]
@@ -651,6 +732,8 @@ pub fn loadavg() -> Result<LoadAvg, Error> {
 }
 
 /// Get current processes quantity.
+///
+/// Use `processes()` to list them on linux.
 pub fn proc_total() -> Result<u64, Error> {
     #[cfg(any(target_os = "solaris", target_os = "illumos"))]
     {
@@ -769,11 +852,44 @@ pub fn mem_info() -> Result<MemInfo, Error> {
     }
 }
 
+/// Get memory information that respects the memory limit of the cgroups of the current process.
+///
+/// On linux, if the limit is smaller than the physical memory, `total` is the limit and `avail`
+/// and `free` are at most what is left under the limit, counting reclaimable page cache as
+/// available. The other values, and the values on other systems, are the same as `mem_info()`.
+pub fn effective_mem_info() -> Result<MemInfo, Error> {
+    let mut info = mem_info()?;
+
+    if let Ok(limits) = cgroup_limits() {
+        if let Some(limit) = limits.memory_limit.map(|limit| limit / 1024).filter(|limit| *limit < info.total) {
+            let working_set = limits.memory_usage.unwrap_or(0).saturating_sub(limits.memory_reclaimable.unwrap_or(0)) / 1024;
+            let left = limit.saturating_sub(working_set);
+
+            info.total = limit;
+            info.avail = info.avail.min(left);
+            info.free = info.free.min(left);
+        }
+    }
+
+    Ok(info)
+}
+
 /// Get disk information.
 ///
-/// Notice, it just calculate current disk on Windows.
+/// Notice, it just calculate current disk on Windows. On linux it's the sum of the writable
+/// filesystems that `filesystems()` returns, use that function to see them one by one.
 pub fn disk_info() -> Result<DiskInfo, Error> {
-    #[cfg(any(target_os = "linux", target_vendor = "apple", target_os = "windows", target_os = "haiku"))]
+    #[cfg(target_os = "linux")]
+    {
+        let writable = disk::writable_filesystems()?.into_iter();
+        let (total, free) = writable.fold((0, 0), |(total, free), filesystem| (total + filesystem.total, free + filesystem.available));
+
+        Ok(DiskInfo {
+            total: total / 1000,
+            free: free / 1000,
+        })
+    }
+    #[cfg(any(target_vendor = "apple", target_os = "windows", target_os = "haiku"))]
     {
         Ok(unsafe { get_disk_info() })
     }
@@ -942,7 +1058,7 @@ pub struct WindowsGraphicsCard {
     pub creation_classname: Vec<String>
 }
 
-/// get the graphic card infos, for windows.
+/// get the graphic card infos, for windows. `graphics_adapters()` returns the main ones of them on both windows and linux.
 pub fn get_graphics_info() -> std::result::Result<WindowsGraphicsCard, std::io::Error> {
     if !cfg!(target_os = "windows") {
         return Err(std::io::Error::new(std::io::ErrorKind::Other, "The 'get_graphics_info()' function is only available on windows."));
@@ -1081,97 +1197,38 @@ pub fn get_graphics_info() -> std::result::Result<WindowsGraphicsCard, std::io::
     })
 }
 
-/// get the computer type. Only "Notebook" and "Desktop" allowed for linux, checks if a battery is exist that implemented on your computer and if it exists, return "Notebook" value, otherwise "Desktop" value. But the way it work on windows is different, it can return various values since windows has able to give more specific infos about computer types. 
+/// get the chassis type of the computer, such as `ChassisType::Notebook`, `ChassisType::Tower` or `ChassisType::RackMountChassis`. The SMBIOS chassis type is read from /sys/class/dmi/id/chassis_type on linux and from win32_systemenclosure on windows, and both use the same `ChassisType` table. If linux has no DMI information, such as on most ARM boards and some virtual machines, it falls back to `ChassisType::Notebook` if `power_supplies()` has a battery that powers your computer and `ChassisType::Desktop` otherwise.
 #[cfg(any(target_os = "windows", target_os = "linux"))]
-pub fn check_computer_type<'a>() -> std::result::Result<&'a str, Error> {
-    use std::process::{Command, Output};
-    use std::str;
-    let mut result = "Unknown";
-
+pub fn check_computer_type() -> std::result::Result<ChassisType, Error> {
     #[cfg(target_os = "windows")]
     {
-        fn computer_type_cleanup_for_windows<'a>(our_output: Output) -> &'a str {
-            let get_output = str::from_utf8(&our_output.stdout).unwrap()
-                                            .replace("\r", "")
-                                            .replace("\n", "")
-                                            .replace("chassistypes", "")
-                                            .replace("-", "")
-                                            .replace(" ", "");
-    
-            return match get_output.as_str() {
-                "{1}" => "Other", "{2}" => "Unknown", "{3}" => "Desktop", "{4}" => "Low Profile Desktop", "{5}" => "Pizza Box",
-                "{6}" => "Mini Tower", "{7}" => "Tower", "{8}" => "Portable", "{9}" => "Laptop", "{10}" => "Notebook", "{11}" => "Handheld", 
-                "{12}" => "Docking Station", "{13}" => "All-in-One", "{14}" => "Sub-Notebook", "{15}" => "Space Saving", "{16}" => "Lunch Box", 
-                "{17}" => "Main System Chassis", "{18}" => "Expansion Chassis", "{19}" => "Sub-Chassis", "{20}" => "Bus Expansion Chassis", 
-                "{21}" => "Peripheral Chassis", "{22}" => "Storage Chassis", "{23}" => "Rack Mount Chassis", "{24}" => "Sealed-Case PC",
-                &_ => "Unknown" 
-            }
-        }
-    
-        let chassis_type_number = Command::new("powershell")
+        let chassis_type_output = std::process::Command::new("powershell")
                                             .arg("Get-WmiObject")
                                             .arg("win32_systemenclosure | select chassistypes")
-                                            .output();
-    
-        match chassis_type_number {
-            Ok(chassis_num) => result = computer_type_cleanup_for_windows(chassis_num),
-            Err(err) => return Err(Error::ExecFailed(err))
-        };
+                                            .output()
+                                            .map_err(Error::ExecFailed)?;
 
-        
-    };
+        // the output is a table like "chassistypes\n------------\n{10}", the first number between the braces is used.
+        let output = String::from_utf8_lossy(&chassis_type_output.stdout);
+        let chassis_type_number = output.split(|c: char| !c.is_ascii_digit())
+                                            .find(|number| !number.is_empty())
+                                            .and_then(|number| number.parse::<u8>().ok());
+
+        Ok(chassis_type_number.map(ChassisType::from_smbios).unwrap_or(ChassisType::Unknown))
+    }
 
     #[cfg(target_os = "linux")]
     {
-        let check_bat0 = Command::new("sh")
-                            .arg("-c")
-                            .arg("test -d /sys/class/power_supply/BAT0")
-                            .output();
-
-
-        let check_bat0 = match check_bat0 {
-            Ok(bat) => bat.status.success(),
-            Err(error) => return Err(Error::ExecFailed(error))
-        };
-
-        let check_bat1 = Command::new("sh")
-                            .arg("-c")
-                            .arg("test -d /sys/class/power_supply/BAT1")
-                            .output();
-
-        let check_bat1 = match check_bat1 {
-            Ok(bat) => bat.status.success(),
-            Err(error) => return Err(Error::ExecFailed(error))
-        };
-
-        let check_bat2 = Command::new("sh")
-                            .arg("-c")
-                            .arg("test -d /sys/class/power_supply/BAT2")
-                            .output();
-
-        let check_bat2 = match check_bat2 {
-            Ok(bat) => bat.status.success(),
-            Err(error) => return Err(Error::ExecFailed(error))
-        };
-
-        let check_bat3 = Command::new("sh")
-                            .arg("-c")
-                            .arg("test -d /sys/class/power_supply/BAT3")
-                            .output();
-
-        let check_bat3 = match check_bat3 {
-            Ok(bat) => bat.status.success(),
-            Err(error) => return Err(Error::ExecFailed(error))
-        };
+        if let Some(chassis_type_number) = sysfs::read_string("/sys/class/dmi/id/chassis_type").and_then(|number| number.parse::<u8>().ok()) {
+            return Ok(ChassisType::from_smbios(chassis_type_number));
+        }
 
-        if check_bat0 || check_bat1 || check_bat2 || check_bat3 {
-            result = "Notebook";
+        if power_supplies()?.iter().any(|supply| supply.is_system_battery()) {
+            Ok(ChassisType::Notebook)
         } else {
-            result = "Desktop";
+            Ok(ChassisType::Desktop)
         }
     }
-
-    return Ok(result);
 }
 
 
@@ -1269,44 +1326,9 @@ pub fn get_public_ipv4_address() -> std::result::Result<String, Error> {
     return Ok(ip_address)
 }
 
-/// that function searchs a program on the terminal if it's exist and / or returns a positive answer to various version arguments. Works on both Windows And Linux.
+/// that function checks if a program is installed by searching it in the PATH with `which()`, without running it. Works on both Windows And Linux.
 pub fn is_program_installed(program: &str) -> bool {
-    let check1 = std::process::Command::new(program).output();
-
-    match check1 {
-        Ok(_) => return true,
-        Err(_) => ()
-    };
-
-    let check2 = std::process::Command::new(program).arg("version").output();
-
-    match check2 {
-        Ok(_) => return true,
-        Err(_) => ()
-    };
-
-    let check3 = std::process::Command::new(program).arg("--version").output();
-
-    match check3 {
-        Ok(_) => return true,
-        Err(_) => ()
-    }
-
-    let check4 = std::process::Command::new(program).arg("-version").output();
-
-    match check4 {
-        Ok(_) => return true,
-        Err(_) => ()
-    }
-
-    let check5 = std::process::Command::new(program).arg("-v").output();
-
-    match check5 {
-        Ok(_) => return true,
-        Err(_) => ()
-    }
-
-    return false
+    find_executable(program).is_some()
 }
 
 
@@ -1318,9 +1340,35 @@ pub struct HardSearchOptions{
 }
 
 /// Since windows has a bunch of api's that enlists downloaded programs and not all of them reachable via a terminal, that function searchs a program with given name and options on both terminal and various program listing api's of windows. Warning: It runs too slow. Use it with caution.
+///
+/// On linux any `search_hardness` above 0 searches the PATH and then the package names of `installed_packages()`, with `case_sensitive` deciding how the names are compared.
 pub fn is_program_installed_search_hard(program: &str, options: HardSearchOptions) -> std::result::Result<bool, std::io::Error> {
+    if cfg!(target_os = "linux") {
+        if options.search_hardness == 0 {
+            return Ok(false);
+        }
+
+        if is_program_installed(program) {
+            return Ok(true);
+        }
+
+        // a system without a supported package database has no package that could match.
+        let packages = package::read_installed_packages().map_err(|error| match error {
+            Error::IO(error) => error,
+            error => std::io::Error::new(std::io::ErrorKind::Other, error.to_string())
+        })?.unwrap_or_default();
+
+        return Ok(packages.iter().any(|package| {
+            if options.case_sensitive {
+                package.name == program
+            } else {
+                package.name.to_lowercase() == program.to_lowercase()
+            }
+        }));
+    }
+
     if !cfg!(target_os = "windows") {
-        return Err(std::io::Error::new(std::io::ErrorKind::Other, "The 'is_program_installed_search_hard()' function is only available on windows."));
+        return Err(std::io::Error::new(std::io::ErrorKind::Other, "The 'is_program_installed_search_hard()' function is only available on windows and linux."));
     }
 
     if options.search_hardness == 0 {
@@ -1514,10 +1562,25 @@ pub struct RamInFo {
     pub ddr_type: String
 }
 
-/// returns the `RamInfo` struct per each ram that attached your computer, that includes the mhz value and ddr type, for only windows. 
+/// returns the `RamInfo` struct per each ram that attached your computer, that includes the mhz value and ddr type, for windows and linux. 
+///
+/// On linux it's built from `memory_devices()`, which needs root to read the SMBIOS table, and the ddr type is the real memory type such as "ddr4" or "lpddr5".
 pub fn get_ram_infos() -> std::result::Result<Vec<RamInFo>, std::io::Error> {
+    if cfg!(target_os = "linux") {
+        let devices = match memory_devices() {
+            Ok(devices) => devices,
+            Err(Error::IO(error)) => return Err(error),
+            Err(error) => return Err(std::io::Error::new(std::io::ErrorKind::Other, error.to_string())),
+        };
+
+        return Ok(devices.into_iter().filter(|device| device.is_installed()).map(|device| RamInFo {
+            mhz: device.configured_speed.or(device.speed).unwrap_or(0) as i32,
+            ddr_type: device.memory_type.name().to_lowercase(),
+        }).collect());
+    }
+
     if !cfg!(target_os = "windows") {
-        return Err(std::io::Error::new(std::io::ErrorKind::Other, "The 'get_ram_infos()' function is only available on windows."));
+        return Err(std::io::Error::new(std::io::ErrorKind::Other, "The 'get_ram_infos()' function is only available on windows and linux."));
     }
 
     let ram_info_command = std::process::Command::new("wmic").arg("memorychip").arg("get").arg("speed").output();
@@ -2063,6 +2126,39 @@ mod test {
         println!("cpu_speed(): {}", speed);
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    pub fn test_cpu_times() {
+        let times = cpu_times().unwrap();
+        assert!(times.total.total() > 0);
+        assert!(!times.per_core.is_empty());
+        println!("cpu_times(): {:?}", times);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    pub fn test_cpu_frequencies() {
+        let frequencies = cpu_frequencies().unwrap();
+        assert!(!frequencies.is_empty());
+        println!("cpu_frequencies(): {:?}", frequencies);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    pub fn test_cpu_topology() {
+        let topology = cpu_topology().unwrap();
+        assert!(topology.physical_core_count() > 0);
+        println!("cpu_topology(): {:?}", topology);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    pub fn test_cpu_info() {
+        let info = cpu_info().unwrap();
+        assert!(!info.architecture.is_empty());
+        println!("cpu_info(): {:?}", info);
+    }
+
     #[test]
     pub fn test_loadavg() {
         let load = loadavg().unwrap();
@@ -2076,6 +2172,36 @@ mod test {
         println!("proc_total(): {}", procs);
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    pub fn test_processes() {
+        let own_pid = std::process::id();
+        let own_process = processes().unwrap().find(|process| process.pid == own_pid).unwrap();
+        assert!(own_process.threads > 0);
+        println!("processes(): {:?}", own_process);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    pub fn test_process() {
+        let details = process(std::process::id()).unwrap();
+        assert!(!details.cmdline.unwrap().is_empty());
+        assert!(details.exe.is_ok());
+        assert!(!details.fds.unwrap().is_empty());
+        println!("process(): {:?}", details.limits);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    pub fn test_process_tree() {
+        let tree = process_tree().unwrap();
+        let own_pid = std::process::id();
+        let parent_pid = std::os::unix::process::parent_id();
+        assert!(tree.ancestors(own_pid).iter().any(|process| process.pid == parent_pid));
+        assert!(tree.descendants(parent_pid).iter().any(|process| process.pid == own_pid));
+        println!("process_tree():\n{}", tree);
+    }
+
     #[test]
     pub fn test_mem_info() {
         let mem = mem_info().unwrap();
@@ -2090,6 +2216,22 @@ mod test {
         println!("disk_info(): {:?}", info);
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    pub fn test_mounts() {
+        let mounts = mounts().unwrap();
+        assert!(mounts.iter().any(|mount| mount.mount_point == "/"));
+        println!("mounts(): {:?}", mounts);
+        println!("filesystems(): {:?}", filesystems().unwrap());
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    pub fn test_disk_io_stats() {
+        let stats = disk_io_stats().unwrap();
+        println!("disk_io_stats(): {:?}", stats);
+    }
+
     #[test]
     pub fn test_hostname() {
         let host = hostname().unwrap();
@@ -2107,8 +2249,8 @@ mod test {
     #[cfg(any(target_os = "linux", target_os = "windows"))]
     #[test]
     pub fn test_check_computer_type() {
-        let pc_type = check_computer_type();
-        println!("computer type: {}", pc_type.unwrap());
+        let pc_type = check_computer_type().unwrap();
+        println!("computer type: {}, portable: {}, server: {}", pc_type, pc_type.is_portable(), pc_type.is_server());
     }
 
     #[test]
@@ -2126,12 +2268,145 @@ mod test {
         println!("linux_os_release(): {:?}", os_release.name)
     }
 
+    #[test]
+    #[cfg(target_os = "linux")]
+    pub fn test_network_interfaces() {
+        let interfaces = network_interfaces().unwrap();
+        assert!(interfaces.iter().any(|interface| interface.flags.loopback));
+        println!("network_interfaces(): {:?}", interfaces);
+    }
+
+    #[test]
+    #[cfg(target_os = "linux")]
+    pub fn test_network_io_stats() {
+        let stats = network_io_stats().unwrap();
+        assert!(!stats.is_empty());
+        println!("network_io_stats(): {:?}", stats);
+    }
+
     #[cfg(any(target_os = "windows", target_os = "linux"))]
     #[test]
     pub fn test_get_public_ipv4_address(){
         assert_ne!(String::new(), get_public_ipv4_address().unwrap())
     }
 
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_which() {
+        let paths = which("sh");
+        assert!(!paths.is_empty());
+        assert!(paths.iter().all(|path| path.is_absolute()));
+        println!("which(): {:?}", paths);
+
+        assert!(is_program_installed("sh"));
+        assert!(!is_program_installed("surely-not-an-installed-program"));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_usb_devices() {
+        println!("usb_devices(): {:?}", usb_devices().unwrap());
+    }
+
+    #[cfg(any(target_os = "linux", target_os = "windows"))]
+    #[test]
+    pub fn test_graphics_adapters() {
+        println!("graphics_adapters(): {:?}", graphics_adapters().unwrap());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_pci_devices() {
+        println!("pci_devices(): {:?}", pci_devices().unwrap());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_memory_devices() {
+        println!("memory_devices(): {:?}", memory_devices());
+        println!("get_ram_infos(): {:?}", get_ram_infos());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_system_identity() {
+        println!("system_identity(): {:?}", system_identity());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_power_supplies() {
+        let supplies = power_supplies().unwrap();
+        println!("power_supplies(): {:?}", supplies);
+        println!("check_computer_type(): {:?}", check_computer_type());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_sensors() {
+        let sensors = sensors().unwrap();
+        for reading in sensors.hwmon.iter() {
+            println!("sensors(): {} {}: {:?} {}", reading.chip, reading.label, reading.value, reading.kind.unit());
+        }
+        println!("sensors(): thermal zones: {:?}", sensors.thermal_zones);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_virtualization() {
+        let virtualization = virtualization().unwrap();
+        println!("virtualization(): {:?}, bare metal: {}", virtualization, virtualization.is_bare_metal());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_cgroup_limits() {
+        println!("cgroup_limits(): {:?}", cgroup_limits());
+
+        let cpu_num = effective_cpu_num().unwrap();
+        assert!(cpu_num >= 1 && cpu_num <= super::cpu_num().unwrap());
+        println!("effective_cpu_num(): {}", cpu_num);
+
+        let mem_info = effective_mem_info().unwrap();
+        assert!(mem_info.total <= super::mem_info().unwrap().total);
+        println!("effective_mem_info(): {:?}", mem_info);
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_pressure() {
+        match pressure() {
+            Ok(pressure) => {
+                assert!(pressure.cpu.some.is_some());
+                println!("pressure(): {:?}", pressure);
+            },
+            Err(Error::UnsupportedSystem) => println!("pressure(): psi is disabled"),
+            Err(error) => panic!("pressure(): {}", error),
+        }
+
+        println!("cgroup_pressure(): {:?}", cgroup_pressure(None));
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_installed_packages() {
+        if let Ok(packages) = installed_packages() {
+            assert!(packages.iter().all(|package| !package.name.is_empty()));
+            println!("installed_packages(): {} packages, first: {:?}", packages.len(), packages.first());
+        }
+
+        let options = HardSearchOptions { case_sensitive: false, search_hardness: 1 };
+        assert!(!is_program_installed_search_hard("surely-not-an-installed-program", options).unwrap());
+    }
+
+    #[cfg(target_os = "linux")]
+    #[test]
+    pub fn test_program_version() {
+        let version = program_version("cargo").unwrap();
+        assert!(version >= Version::new(1, 0, 0));
+        println!("program_version(): {}", version);
+    }
+
     #[cfg(target_os = "windows")]
     #[test]
     pub fn test_is_program_installed_search_hard(){
@@ -0,0 +1,362 @@
+//! Network interfaces and traffic counters, read with getifaddrs and from /sys/class/net and /proc/net/dev on linux.
+
+use std::fs::File;
+use std::io::Read;
+use std::net::IpAddr;
+use std::time::{Duration, Instant};
+
+use super::Error;
+#[cfg(target_os = "linux")]
+use sysfs;
+
+/// Flags of a network interface, see `man 7 netdevice`.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct InterfaceFlags {
+    /// The interface is administratively up.
+    pub up: bool,
+    /// The interface has a working link and resources are allocated.
+    pub running: bool,
+    pub loopback: bool,
+    pub broadcast: bool,
+    pub multicast: bool,
+    pub point_to_point: bool,
+}
+
+/// An ip address that is assigned to an interface.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct InterfaceAddress {
+    pub address: IpAddr,
+    /// Length of the network prefix, such as 24 for a 255.255.255.0 netmask.
+    pub prefix_len: u8,
+}
+
+/// A network interface with its addresses and link information.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetworkInterface {
+    /// Such as "eth0", "wlp2s0" or "lo".
+    pub name: String,
+    pub index: u32,
+    pub flags: InterfaceFlags,
+    /// Hardware address such as "02:fc:00:00:00:01". The loopback interface reports all zeros.
+    pub mac_address: Option<String>,
+    pub mtu: Option<u32>,
+    pub addresses: Vec<InterfaceAddress>,
+    /// Link speed in Mbit/s, `None` if the link is down or the driver doesn't report it.
+    pub speed: Option<u32>,
+    /// "full", "half" or "unknown".
+    pub duplex: Option<String>,
+    /// RFC 2863 operational state, such as "up", "down", "dormant" or "unknown".
+    pub operstate: Option<String>,
+}
+
+/// Get every network interface with its flags, mac address, mtu, ipv4 and ipv6 addresses and link state, only for linux.
+///
+/// Addresses and flags come from getifaddrs, the rest from `/sys/class/net/<name>`.
+pub fn network_interfaces() -> Result<Vec<NetworkInterface>, Error> {
+    #[cfg(target_os = "linux")]
+    {
+        let mut interfaces = getifaddrs()?;
+
+        // interfaces without any address may be missing from getifaddrs.
+        if let Ok(entries) = std::fs::read_dir("/sys/class/net") {
+            for entry in entries.flatten() {
+                let name = entry.file_name().to_string_lossy().into_owned();
+
+                if !interfaces.iter().any(|interface| interface.name == name) {
+                    interfaces.push(NetworkInterface { name, ..Default::default() });
+                }
+            }
+        }
+
+        for interface in interfaces.iter_mut() {
+            let path = std::path::Path::new("/sys/class/net").join(&interface.name);
+
+            interface.index = sysfs::read_u64(path.join("ifindex")).unwrap_or(0) as u32;
+            interface.mac_address = sysfs::read_string(path.join("address")).filter(|address| !address.is_empty());
+            interface.mtu = sysfs::read_u64(path.join("mtu")).map(|mtu| mtu as u32);
+            // reading the speed fails with EINVAL when the link is down, and some drivers report -1.
+            interface.speed = sysfs::read_u64(path.join("speed")).filter(|speed| *speed > 0).map(|speed| speed as u32);
+            interface.duplex = sysfs::read_string(path.join("duplex"));
+            interface.operstate = sysfs::read_string(path.join("operstate"));
+
+            if interface.flags == InterfaceFlags::default() {
+                if let Some(flags) = sysfs::read_string(path.join("flags")).and_then(|flags| u32::from_str_radix(flags.trim_start_matches("0x"), 16).ok()) {
+                    interface.flags = flags_from_raw(flags);
+                }
+            }
+        }
+
+        interfaces.sort_by_key(|interface| interface.index);
+        Ok(interfaces)
+    }
+    #[cfg(not(target_os = "linux"))]
+    {
+        Err(Error::UnsupportedSystem)
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn flags_from_raw(flags: u32) -> InterfaceFlags {
+    let has = |flag: libc::c_int| flags & flag as u32 != 0;
+
+    InterfaceFlags {
+        up: has(libc::IFF_UP),
+        running: has(libc::IFF_RUNNING),
+        loopback: has(libc::IFF_LOOPBACK),
+        broadcast: has(libc::IFF_BROADCAST),
+        multicast: has(libc::IFF_MULTICAST),
+        point_to_point: has(libc::IFF_POINTOPOINT),
+    }
+}
+
+#[cfg(target_os = "linux")]
+fn getifaddrs() -> Result<Vec<NetworkInterface>, Error> {
+    use std::ffi::CStr;
+    use std::net::{Ipv4Addr, Ipv6Addr};
+
+    let mut interfaces: Vec<NetworkInterface> = vec![];
+
+    unsafe {
+        let mut ifap: *mut libc::ifaddrs = std::ptr::null_mut();
+        if libc::getifaddrs(&mut ifap) != 0 {
+            return Err(Error::IO(std::io::Error::last_os_error()));
+        }
+
+        let mut cursor = ifap;
+        while !cursor.is_null() {
+            let ifa = &*cursor;
+            cursor = ifa.ifa_next;
+
+            let name = CStr::from_ptr(ifa.ifa_name).to_string_lossy().into_owned();
+            let position = match interfaces.iter().position(|interface| interface.name == name) {
+                Some(position) => position,
+                None => {
+                    interfaces.push(NetworkInterface { name, flags: flags_from_raw(ifa.ifa_flags), ..Default::default() });
+                    interfaces.len() - 1
+                }
+            };
+
+            if ifa.ifa_addr.is_null() {
+                continue;
+            }
+
+            let address = match (*ifa.ifa_addr).sa_family as libc::c_int {
+                libc::AF_INET => {
+                    let addr = &*(ifa.ifa_addr as *const libc::sockaddr_in);
+                    let prefix_len = if ifa.ifa_netmask.is_null() { 32 } else {
+                        let mask = &*(ifa.ifa_netmask as *const libc::sockaddr_in);
+                        u32::from_be(mask.sin_addr.s_addr).count_ones() as u8
+                    };
+
+                    InterfaceAddress { address: IpAddr::V4(Ipv4Addr::from(u32::from_be(addr.sin_addr.s_addr))), prefix_len }
+                },
+                libc::AF_INET6 => {
+                    let addr = &*(ifa.ifa_addr as *const libc::sockaddr_in6);
+                    let prefix_len = if ifa.ifa_netmask.is_null() { 128 } else {
+                        let mask = &*(ifa.ifa_netmask as *const libc::sockaddr_in6);
+                        mask.sin6_addr.s6_addr.iter().map(|byte| byte.count_ones()).sum::<u32>() as u8
+                    };
+
+                    InterfaceAddress { address: IpAddr::V6(Ipv6Addr::from(addr.sin6_addr.s6_addr)), prefix_len }
+                },
+                _ => continue,
+            };
+
+            interfaces[position].addresses.push(address);
+        }
+
+        libc::freeifaddrs(ifap);
+    }
+
+    Ok(interfaces)
+}
+
+/// Traffic counters of one network interface since it was created, a line of /proc/net/dev.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetworkIoStats {
+    pub name: String,
+    pub rx_bytes: u64,
+    pub rx_packets: u64,
+    pub rx_errors: u64,
+    pub rx_dropped: u64,
+    pub rx_fifo: u64,
+    pub rx_frame: u64,
+    pub rx_compressed: u64,
+    pub rx_multicast: u64,
+    pub tx_bytes: u64,
+    pub tx_packets: u64,
+    pub tx_errors: u64,
+    pub tx_dropped: u64,
+    pub tx_fifo: u64,
+    pub tx_collisions: u64,
+    pub tx_carrier: u64,
+    pub tx_compressed: u64,
+}
+
+/// Traffic rates of one network interface between two `NetworkIoStats` samples.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct NetworkIoRate {
+    pub name: String,
+    pub rx_bytes_per_sec: f64,
+    pub tx_bytes_per_sec: f64,
+    pub rx_packets_per_sec: f64,
+    pub tx_packets_per_sec: f64,
+}
+
+/// Get the traffic counters of every network interface from /proc/net/dev, only for linux.
+pub fn network_io_stats() -> Result<Vec<NetworkIoStats>, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    let mut s = String::new();
+    File::open("/proc/net/dev")?.read_to_string(&mut s)?;
+
+    Ok(parse_net_dev(&s))
+}
+
+fn parse_net_dev(s: &str) -> Vec<NetworkIoStats> {
+    let mut stats = vec![];
+
+    // the first two lines are the header.
+    for line in s.lines().skip(2) {
+        let mut split_line = line.splitn(2, ':');
+        let name = split_line.next().unwrap_or("").trim();
+        let values = split_line.next().unwrap_or("")
+            .split_whitespace()
+            .map(|value| value.parse::<u64>().unwrap_or(0))
+            .collect::<Vec<u64>>();
+
+        if name.is_empty() || values.len() < 16 {
+            continue;
+        }
+
+        stats.push(NetworkIoStats {
+            name: name.to_string(),
+            rx_bytes: values[0],
+            rx_packets: values[1],
+            rx_errors: values[2],
+            rx_dropped: values[3],
+            rx_fifo: values[4],
+            rx_frame: values[5],
+            rx_compressed: values[6],
+            rx_multicast: values[7],
+            tx_bytes: values[8],
+            tx_packets: values[9],
+            tx_errors: values[10],
+            tx_dropped: values[11],
+            tx_fifo: values[12],
+            tx_collisions: values[13],
+            tx_carrier: values[14],
+            tx_compressed: values[15],
+        });
+    }
+
+    stats
+}
+
+/// Difference of two counter readings. The counters of 32 bit kernels wrap at 2^32, whatever the
+/// build target of this program is, so a counter that went backwards from a value that fits in
+/// 32 bits wrapped. Any other decrease means the counter was reset or its interface was
+/// recreated, and there is no difference to count.
+fn counter_delta(prev: u64, cur: u64) -> u64 {
+    if cur >= prev {
+        cur - prev
+    } else if prev <= u32::MAX as u64 {
+        (u32::MAX as u64 - prev) + cur + 1
+    } else {
+        0
+    }
+}
+
+/// Calculates the traffic rates between two samples, `elapsed` is the time between them.
+///
+/// Only the interfaces that exist in both samples are returned, interfaces that appeared or
+/// disappeared in between are left out.
+pub fn network_io_rates_between(prev: &[NetworkIoStats], cur: &[NetworkIoStats], elapsed: Duration) -> Vec<NetworkIoRate> {
+    let secs = elapsed.as_secs_f64();
+    if secs <= 0.0 {
+        return vec![];
+    }
+
+    cur.iter()
+        .filter_map(|current| {
+            let old = prev.iter().find(|old| old.name == current.name)?;
+
+            Some(NetworkIoRate {
+                name: current.name.clone(),
+                rx_bytes_per_sec: counter_delta(old.rx_bytes, current.rx_bytes) as f64 / secs,
+                tx_bytes_per_sec: counter_delta(old.tx_bytes, current.tx_bytes) as f64 / secs,
+                rx_packets_per_sec: counter_delta(old.rx_packets, current.rx_packets) as f64 / secs,
+                tx_packets_per_sec: counter_delta(old.tx_packets, current.tx_packets) as f64 / secs,
+            })
+        })
+        .collect()
+}
+
+/// Keeps the last `network_io_stats()` sample and reports the traffic rates since then on every sample.
+#[derive(Debug, Clone)]
+pub struct NetworkIoSampler {
+    last: Vec<NetworkIoStats>,
+    taken_at: Instant,
+}
+
+impl NetworkIoSampler {
+    /// Takes the first sample.
+    pub fn new() -> Result<NetworkIoSampler, Error> {
+        Ok(NetworkIoSampler { last: network_io_stats()?, taken_at: Instant::now() })
+    }
+
+    /// Takes a new sample and returns the traffic rates since the previous one.
+    pub fn sample(&mut self) -> Result<Vec<NetworkIoRate>, Error> {
+        let current = network_io_stats()?;
+        let now = Instant::now();
+        let rates = network_io_rates_between(&self.last, &current, now.duration_since(self.taken_at));
+
+        self.last = current;
+        self.taken_at = now;
+
+        Ok(rates)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const NET_DEV: &str = "Inter-|   Receive                                                |  Transmit
+ face |bytes    packets errs drop fifo frame compressed multicast|bytes    packets errs drop fifo colls carrier compressed
+    lo: 1000    10    0    0    0     0          0         0 1000    10    0    0    0     0       0          0
+  eth0: 4294967000     200    1    2    0     0          0         3    22400     213    0    0    0     0       0          0
+";
+
+    #[test]
+    pub fn test_parse_net_dev() {
+        let stats = parse_net_dev(NET_DEV);
+        assert_eq!(stats.len(), 2);
+        assert_eq!(stats[1].name, "eth0");
+        assert_eq!((stats[1].rx_errors, stats[1].rx_dropped, stats[1].rx_multicast), (1, 2, 3));
+        assert_eq!(stats[1].tx_packets, 213);
+    }
+
+    #[test]
+    pub fn test_network_io_rates_between() {
+        let prev = parse_net_dev(NET_DEV);
+        let cur = parse_net_dev("header\nheader\n  eth0: 1000 400 0 0 0 0 0 0 32400 413 0 0 0 0 0 0\n  wlan0: 5 5 0 0 0 0 0 0 5 5 0 0 0 0 0 0\n");
+
+        let rates = network_io_rates_between(&prev, &cur, Duration::from_secs(2));
+        assert_eq!(rates.len(), 1);
+        // the rx byte counter went backwards from below 2^32, it wrapped.
+        assert_eq!(rates[0].rx_bytes_per_sec, (4294967296.0 - 4294967000.0 + 1000.0) / 2.0);
+        assert_eq!(rates[0].tx_bytes_per_sec, 5000.0);
+        assert_eq!(rates[0].rx_packets_per_sec, 100.0);
+    }
+
+    #[test]
+    pub fn test_counter_delta() {
+        assert_eq!(counter_delta(100, 250), 150);
+        assert_eq!(counter_delta(u32::MAX as u64 - 9, 5), 15);
+        // a 64 bit counter can't wrap, it was reset.
+        assert_eq!(counter_delta(u32::MAX as u64 + 10, 5), 0);
+    }
+}
@@ -0,0 +1,638 @@
+//! Installed packages, read from the databases of dpkg, apk, pacman and rpm on linux.
+
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use super::Error;
+
+const DPKG_STATUS: &str = "/var/lib/dpkg/status";
+const APK_INSTALLED: &str = "/lib/apk/db/installed";
+const PACMAN_LOCAL: &str = "/var/lib/pacman/local";
+/// The sqlite databases of rpm 4.16 and newer, the first one is used by newer distributions.
+const RPM_SQLITE: &[&str] = &["/usr/lib/sysimage/rpm/rpmdb.sqlite", "/var/lib/rpm/rpmdb.sqlite"];
+
+const RPMTAG_NAME: u32 = 1000;
+const RPMTAG_VERSION: u32 = 1001;
+const RPMTAG_RELEASE: u32 = 1002;
+const RPMTAG_EPOCH: u32 = 1003;
+const RPMTAG_SIZE: u32 = 1009;
+const RPMTAG_ARCH: u32 = 1022;
+const RPMTAG_LONGSIZE: u32 = 5009;
+
+/// The package manager whose database a `Package` is read from.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum PackageManager {
+    Dpkg,
+    Apk,
+    Pacman,
+    Rpm,
+}
+
+/// A package that is installed by the package manager of the distribution.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Package {
+    pub name: String,
+    /// The full version as the package manager shows it, such as "1:2.39.2-1ubuntu1" or "2.39.2-r0".
+    pub version: String,
+    /// Such as "amd64", "x86_64", "noarch" or "all".
+    pub architecture: Option<String>,
+    /// Installed size in bytes. dpkg records it in KiB, so it's a multiple of 1024 there.
+    pub installed_size: Option<u64>,
+    pub manager: PackageManager,
+}
+
+/// Get the installed packages of every package database on the system, only for linux.
+///
+/// The databases are read from disk without running the package managers: /var/lib/dpkg/status,
+/// /lib/apk/db/installed, /var/lib/pacman/local and the sqlite rpm database of rpm 4.16 and newer,
+/// with the transactions of its write-ahead log that aren't checkpointed yet. The older Berkeley DB
+/// and ndb rpm databases aren't supported. It's an error if none of the databases exist.
+pub fn installed_packages() -> Result<Vec<Package>, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    read_installed_packages()?
+        .ok_or_else(|| Error::General("there is no dpkg, apk, pacman or rpm package database on this system".to_string()))
+}
+
+/// Reads the package databases, None if the system has none of them, such as NixOS and rpm
+/// systems with the older databases.
+pub(crate) fn read_installed_packages() -> Result<Option<Vec<Package>>, Error> {
+    let mut packages = vec![];
+    let mut found = false;
+
+    if let Some(status) = read_if_exists(DPKG_STATUS)? {
+        found = true;
+        packages.extend(parse_dpkg_status(&status));
+    }
+
+    if let Some(installed) = read_if_exists(APK_INSTALLED)? {
+        found = true;
+        packages.extend(parse_apk_installed(&installed));
+    }
+
+    if Path::new(PACMAN_LOCAL).is_dir() {
+        found = true;
+
+        for entry in fs::read_dir(PACMAN_LOCAL)? {
+            // the local database also has an "ALPM_DB_VERSION" file next to the package directories.
+            if let Ok(desc) = fs::read_to_string(entry?.path().join("desc")) {
+                packages.extend(parse_pacman_desc(&desc));
+            }
+        }
+    }
+
+    if let Some(path) = RPM_SQLITE.iter().find(|path| Path::new(path).is_file()) {
+        found = true;
+
+        let database = fs::read(path)?;
+        // rpm opens the database in WAL mode, the changes since the last checkpoint are only in
+        // the -wal file next to it.
+        let wal = match fs::read(format!("{}-wal", path)) {
+            Ok(wal) => wal,
+            Err(ref error) if error.kind() == io::ErrorKind::NotFound => vec![],
+            Err(error) => return Err(Error::IO(error)),
+        };
+        let blobs = sqlite_blobs(&database, &wal, "Packages")
+            .ok_or_else(|| Error::General(format!("cannot read the 'Packages' table of {}", path)))?;
+        packages.extend(blobs.iter().filter_map(|blob| parse_rpm_header(blob)));
+    }
+
+    Ok(if found { Some(packages) } else { None })
+}
+
+fn read_if_exists(path: &str) -> Result<Option<String>, Error> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(content)),
+        Err(ref error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(Error::IO(error)),
+    }
+}
+
+/// Parses the paragraphs of /var/lib/dpkg/status, skipping the packages that are removed
+/// but still have their config files, or are half installed.
+fn parse_dpkg_status(status: &str) -> Vec<Package> {
+    status.split("\n\n").filter_map(|paragraph| {
+        // continuation lines start with a space, so they never match a field name.
+        let field = |name: &str| paragraph.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            if key == name { Some(value.trim()) } else { None }
+        });
+
+        if field("Status")?.split_whitespace().nth(2) != Some("installed") {
+            return None;
+        }
+
+        Some(Package {
+            name: field("Package")?.to_string(),
+            version: field("Version").unwrap_or_default().to_string(),
+            architecture: field("Architecture").map(str::to_string),
+            installed_size: field("Installed-Size").and_then(|size| size.parse::<u64>().ok()).map(|kib| kib * 1024),
+            manager: PackageManager::Dpkg,
+        })
+    }).collect()
+}
+
+/// Parses the paragraphs of /lib/apk/db/installed, where every line is a one letter key, a colon and a value.
+fn parse_apk_installed(installed: &str) -> Vec<Package> {
+    installed.split("\n\n").filter_map(|paragraph| {
+        let field = |name: &str| paragraph.lines().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            if key == name { Some(value) } else { None }
+        });
+
+        Some(Package {
+            name: field("P")?.to_string(),
+            version: field("V").unwrap_or_default().to_string(),
+            architecture: field("A").map(str::to_string),
+            installed_size: field("I").and_then(|size| size.parse::<u64>().ok()),
+            manager: PackageManager::Apk,
+        })
+    }).collect()
+}
+
+/// Parses a /var/lib/pacman/local/<package>/desc file, where every "%KEY%" line is followed by its values.
+fn parse_pacman_desc(desc: &str) -> Option<Package> {
+    let mut name = None;
+    let mut version = None;
+    let mut architecture = None;
+    let mut installed_size = None;
+
+    let mut lines = desc.lines();
+    while let Some(line) = lines.next() {
+        let value = match line {
+            "%NAME%" | "%VERSION%" | "%ARCH%" | "%SIZE%" => lines.next().map(str::trim),
+            _ => continue,
+        };
+
+        match line {
+            "%NAME%" => name = value.map(str::to_string),
+            "%VERSION%" => version = value.map(str::to_string),
+            "%ARCH%" => architecture = value.map(str::to_string),
+            _ => installed_size = value.and_then(|size| size.parse::<u64>().ok()),
+        }
+    }
+
+    Some(Package {
+        name: name?,
+        version: version.unwrap_or_default(),
+        architecture,
+        installed_size,
+        manager: PackageManager::Pacman,
+    })
+}
+
+fn be_u16(bytes: &[u8], offset: usize) -> Option<u16> {
+    let bytes = bytes.get(offset..offset + 2)?;
+    Some(u16::from_be_bytes([bytes[0], bytes[1]]))
+}
+
+fn be_u32(bytes: &[u8], offset: usize) -> Option<u32> {
+    let bytes = bytes.get(offset..offset + 4)?;
+    Some(u32::from_be_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+}
+
+fn be_u64(bytes: &[u8], offset: usize) -> Option<u64> {
+    Some((be_u32(bytes, offset)? as u64) << 32 | be_u32(bytes, offset + 4)? as u64)
+}
+
+/// Parses an rpm header blob as rpm stores it in its database: the index entry count, the data
+/// length, 16 byte index entries (tag, type, offset, count) and the data the entries point into.
+fn parse_rpm_header(blob: &[u8]) -> Option<Package> {
+    let entry_count = be_u32(blob, 0)? as usize;
+    let data_length = be_u32(blob, 4)? as usize;
+    let data_start = entry_count.checked_mul(16)?.checked_add(8)?;
+    let data = blob.get(data_start..data_start.checked_add(data_length)?)?;
+
+    let string_at = |offset: usize| -> Option<String> {
+        let bytes = data.get(offset..)?;
+        let end = bytes.iter().position(|byte| *byte == 0)?;
+        Some(String::from_utf8_lossy(&bytes[..end]).into_owned())
+    };
+
+    let mut name = None;
+    let mut version = None;
+    let mut release = None;
+    let mut epoch = None;
+    let mut architecture = None;
+    let mut size = None;
+    let mut long_size = None;
+
+    for index in 0..entry_count {
+        let entry = &blob[8 + index * 16..];
+        let tag = be_u32(entry, 0)?;
+        let offset = be_u32(entry, 8)? as usize;
+
+        match tag {
+            RPMTAG_NAME => name = string_at(offset),
+            RPMTAG_VERSION => version = string_at(offset),
+            RPMTAG_RELEASE => release = string_at(offset),
+            RPMTAG_ARCH => architecture = string_at(offset),
+            RPMTAG_EPOCH => epoch = be_u32(data, offset),
+            RPMTAG_SIZE => size = be_u32(data, offset).map(u64::from),
+            RPMTAG_LONGSIZE => long_size = be_u64(data, offset),
+            _ => (),
+        }
+    }
+
+    let mut full_version = version.unwrap_or_default();
+    if let Some(release) = release {
+        full_version = format!("{}-{}", full_version, release);
+    }
+    if let Some(epoch) = epoch {
+        full_version = format!("{}:{}", epoch, full_version);
+    }
+
+    Some(Package {
+        name: name?,
+        version: full_version,
+        architecture,
+        installed_size: long_size.or(size),
+        manager: PackageManager::Rpm,
+    })
+}
+
+/// A column value of an sqlite record. Floats aren't needed, so they're read as `Other`.
+#[derive(Debug, PartialEq)]
+enum SqliteValue<'a> {
+    Null,
+    Integer(i64),
+    Text(&'a [u8]),
+    Blob(&'a [u8]),
+    Other,
+}
+
+/// Enough of the sqlite file format to read every row of a table: the table b-tree pages, the overflow pages
+/// and the committed pages of the write-ahead log. See <https://www.sqlite.org/fileformat.html>.
+struct Sqlite<'a> {
+    database: &'a [u8],
+    /// The newest committed version of the pages that the WAL has, they replace the ones of the database.
+    wal_pages: HashMap<u32, &'a [u8]>,
+    page_size: usize,
+    usable_size: usize,
+}
+
+impl<'a> Sqlite<'a> {
+    /// Opens a database with the content of its -wal file, which is empty without one.
+    fn new(database: &'a [u8], wal: &'a [u8]) -> Option<Sqlite<'a>> {
+        if !database.starts_with(b"SQLite format 3\0") {
+            return None;
+        }
+
+        let page_size = match be_u16(database, 16)? {
+            1 => 65536,
+            size => size as usize,
+        };
+        let usable_size = page_size.checked_sub(*database.get(20)? as usize)?;
+
+        if usable_size < 480 {
+            return None;
+        }
+
+        Some(Sqlite { database, wal_pages: wal_pages(wal, page_size), page_size, usable_size })
+    }
+
+    fn page(&self, number: u32) -> Option<&'a [u8]> {
+        if let Some(page) = self.wal_pages.get(&number) {
+            return Some(page);
+        }
+
+        let start = (number as usize).checked_sub(1)?.checked_mul(self.page_size)?;
+        self.database.get(start..start + self.page_size)
+    }
+
+    /// Collects the record payloads of every row in the table b-tree that starts at the given page.
+    fn rows(&self, page_number: u32, rows: &mut Vec<Vec<u8>>, visited: &mut HashSet<u32>, depth: usize) -> Option<()> {
+        // a corrupted database could have a cycle in the b-tree or pages that are shared by subtrees.
+        if depth > 32 || !visited.insert(page_number) {
+            return None;
+        }
+
+        let page = self.page(page_number)?;
+        // the first page starts with the 100 bytes of database header.
+        let header = if page_number == 1 { 100 } else { 0 };
+        let cell_count = be_u16(page, header + 3)? as usize;
+
+        match *page.get(header)? {
+            // interior table page: every cell has a left child page, and the right most child is in the header.
+            0x05 => {
+                for cell in 0..cell_count {
+                    let cell_offset = be_u16(page, header + 12 + cell * 2)? as usize;
+                    self.rows(be_u32(page, cell_offset)?, rows, visited, depth + 1)?;
+                }
+
+                self.rows(be_u32(page, header + 8)?, rows, visited, depth + 1)
+            },
+            // leaf table page: every cell is a payload length, a rowid and the payload.
+            0x0d => {
+                for cell in 0..cell_count {
+                    let cell_offset = be_u16(page, header + 8 + cell * 2)? as usize;
+                    let (payload_length, length_size) = varint(page, cell_offset)?;
+                    let (_, rowid_size) = varint(page, cell_offset + length_size)?;
+                    rows.push(self.payload(page, cell_offset + length_size + rowid_size, payload_length as usize)?);
+                }
+
+                Some(())
+            },
+            _ => None,
+        }
+    }
+
+    /// Reads a payload that starts in a leaf page and continues in a chain of overflow pages if it doesn't fit.
+    fn payload(&self, page: &[u8], start: usize, length: usize) -> Option<Vec<u8>> {
+        let max_local = self.usable_size - 35;
+        let local = if length <= max_local {
+            length
+        } else {
+            let min_local = (self.usable_size - 12) * 32 / 255 - 23;
+            let local = min_local + (length - min_local) % (self.usable_size - 4);
+            if local <= max_local { local } else { min_local }
+        };
+
+        let mut payload = page.get(start..start + local)?.to_vec();
+        let mut next_page = if local < length { be_u32(page, start + local)? } else { 0 };
+
+        while payload.len() < length {
+            let overflow = self.page(next_page)?;
+            let take = (length - payload.len()).min(self.usable_size - 4);
+            payload.extend_from_slice(overflow.get(4..4 + take)?);
+            next_page = be_u32(overflow, 0)?;
+        }
+
+        Some(payload)
+    }
+}
+
+/// Collects the pages of the valid frames of a WAL file up to its last commit, a later frame of a page
+/// replaces the earlier ones. A frame is valid if it has the salts of the WAL header and its checksum,
+/// which covers every frame before it too, is right. The frames after the last commit belong to a
+/// transaction that isn't finished, and the ones with other salts are left over from before a checkpoint.
+fn wal_pages(wal: &[u8], page_size: usize) -> HashMap<u32, &[u8]> {
+    let mut pages = HashMap::new();
+
+    let magic = match be_u32(wal, 0) {
+        Some(magic) if magic & !1 == 0x377f0682 => magic,
+        _ => return pages,
+    };
+    if be_u32(wal, 8) != Some(page_size as u32) {
+        return pages;
+    }
+
+    // the checksums are sums of big endian words if the lowest bit of the magic is set, little endian ones otherwise.
+    let checksum = |bytes: &[u8], (mut first, mut second): (u32, u32)| {
+        for words in bytes.chunks_exact(8) {
+            let word = |index: usize| {
+                let word = [words[index], words[index + 1], words[index + 2], words[index + 3]];
+                if magic & 1 == 1 { u32::from_be_bytes(word) } else { u32::from_le_bytes(word) }
+            };
+            first = first.wrapping_add(word(0)).wrapping_add(second);
+            second = second.wrapping_add(word(4)).wrapping_add(first);
+        }
+        (first, second)
+    };
+
+    let mut sum = checksum(&wal[..24], (0, 0));
+    if (be_u32(wal, 24), be_u32(wal, 28)) != (Some(sum.0), Some(sum.1)) {
+        return pages;
+    }
+
+    let mut transaction = vec![];
+    for frame in wal[32..].chunks_exact(24 + page_size) {
+        sum = checksum(&frame[24..], checksum(&frame[..8], sum));
+
+        if frame[8..16] != wal[16..24] || (be_u32(frame, 16), be_u32(frame, 20)) != (Some(sum.0), Some(sum.1)) {
+            break;
+        }
+
+        transaction.push((be_u32(frame, 0).unwrap_or(0), &frame[24..]));
+
+        // the database size is only set in the last frame of a transaction.
+        if be_u32(frame, 4) != Some(0) {
+            pages.extend(transaction.drain(..));
+        }
+    }
+
+    pages
+}
+
+/// Reads a variable length integer of sqlite, returns the value and the count of bytes it takes.
+fn varint(bytes: &[u8], offset: usize) -> Option<(u64, usize)> {
+    let mut value: u64 = 0;
+
+    for index in 0..9 {
+        let byte = *bytes.get(offset + index)?;
+
+        // the ninth byte contributes all of its eight bits.
+        if index == 8 {
+            return Some((value << 8 | byte as u64, 9));
+        }
+
+        value = value << 7 | (byte & 0x7f) as u64;
+        if byte & 0x80 == 0 {
+            return Some((value, index + 1));
+        }
+    }
+
+    None
+}
+
+/// Splits an sqlite record into its column values.
+fn record_values(record: &[u8]) -> Option<Vec<SqliteValue<'_>>> {
+    let (header_size, mut header_offset) = varint(record, 0)?;
+    let mut data_offset = header_size as usize;
+    let mut values = vec![];
+
+    while header_offset < header_size as usize {
+        let (serial_type, size) = varint(record, header_offset)?;
+        header_offset += size;
+
+        let length = match serial_type {
+            0 | 8 | 9 => 0,
+            1..=4 => serial_type as usize,
+            5 => 6,
+            6 | 7 => 8,
+            10 | 11 => return None,
+            _ => (serial_type as usize - 12) / 2,
+        };
+        let bytes = record.get(data_offset..data_offset + length)?;
+        data_offset += length;
+
+        values.push(match serial_type {
+            0 => SqliteValue::Null,
+            8 => SqliteValue::Integer(0),
+            9 => SqliteValue::Integer(1),
+            1..=6 => {
+                // big endian two's complement, sign extended from its first byte.
+                let first = if bytes[0] & 0x80 != 0 { -1i64 } else { 0 };
+                SqliteValue::Integer(bytes.iter().fold(first, |value, byte| value << 8 | *byte as i64))
+            },
+            7 => SqliteValue::Other,
+            _ if serial_type % 2 == 0 => SqliteValue::Blob(bytes),
+            _ => SqliteValue::Text(bytes),
+        });
+    }
+
+    Some(values)
+}
+
+/// Returns every blob value of every row of an sqlite table. The table is found in the
+/// sqlite_schema table on the first page.
+fn sqlite_blobs(database: &[u8], wal: &[u8], table: &str) -> Option<Vec<Vec<u8>>> {
+    let sqlite = Sqlite::new(database, wal)?;
+
+    let mut schema = vec![];
+    sqlite.rows(1, &mut schema, &mut HashSet::new(), 0)?;
+
+    // the columns of sqlite_schema are type, name, tbl_name, rootpage and sql.
+    let root_page = schema.iter().find_map(|row| {
+        match record_values(row)?.as_slice() {
+            [SqliteValue::Text(b"table"), SqliteValue::Text(name), _, SqliteValue::Integer(root_page), ..] if *name == table.as_bytes() => Some(*root_page as u32),
+            _ => None,
+        }
+    })?;
+
+    let mut rows = vec![];
+    sqlite.rows(root_page, &mut rows, &mut HashSet::new(), 0)?;
+
+    let mut blobs = vec![];
+    for row in rows.iter() {
+        for value in record_values(row)? {
+            if let SqliteValue::Blob(blob) = value {
+                blobs.push(blob.to_vec());
+            }
+        }
+    }
+
+    Some(blobs)
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_parse_dpkg_status() {
+        let status = "Package: git\n\
+Status: install ok installed\n\
+Priority: optional\n\
+Installed-Size: 40000\n\
+Architecture: amd64\n\
+Version: 1:2.39.2-1ubuntu1\n\
+Description: fast, scalable, distributed revision control system\n \
+Git is popular version control system.\n\
+\n\
+Package: old-package\n\
+Status: deinstall ok config-files\n\
+Architecture: all\n\
+Version: 1.0\n\
+\n\
+Package: tzdata\n\
+Status: install ok installed\n\
+Architecture: all\n\
+Version: 2024a-0ubuntu1\n";
+
+        let packages = parse_dpkg_status(status);
+
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0], Package {
+            name: "git".to_string(),
+            version: "1:2.39.2-1ubuntu1".to_string(),
+            architecture: Some("amd64".to_string()),
+            installed_size: Some(40000 * 1024),
+            manager: PackageManager::Dpkg,
+        });
+        assert_eq!(packages[1].name, "tzdata");
+        assert_eq!(packages[1].installed_size, None);
+    }
+
+    #[test]
+    pub fn test_parse_apk_installed() {
+        let installed = "C:Q1abc=\nP:musl\nV:1.2.4-r2\nA:x86_64\nS:383269\nI:622592\nT:the musl c library\n\nC:Q1def=\nP:busybox\nV:1.36.1-r5\nA:x86_64\nI:946176\n";
+
+        let packages = parse_apk_installed(installed);
+
+        assert_eq!(packages.len(), 2);
+        assert_eq!(packages[0].name, "musl");
+        assert_eq!(packages[0].version, "1.2.4-r2");
+        assert_eq!(packages[0].architecture.as_deref(), Some("x86_64"));
+        assert_eq!(packages[0].installed_size, Some(622592));
+        assert_eq!(packages[1].name, "busybox");
+    }
+
+    #[test]
+    pub fn test_parse_pacman_desc() {
+        let desc = "%NAME%\ngit\n\n%VERSION%\n2.43.0-1\n\n%BASE%\ngit\n\n%ARCH%\nx86_64\n\n%SIZE%\n26329107\n\n%DEPENDS%\ncurl\nexpat\n";
+
+        let package = parse_pacman_desc(desc).unwrap();
+
+        assert_eq!(package.name, "git");
+        assert_eq!(package.version, "2.43.0-1");
+        assert_eq!(package.architecture.as_deref(), Some("x86_64"));
+        assert_eq!(package.installed_size, Some(26329107));
+        assert_eq!(package.manager, PackageManager::Pacman);
+    }
+
+    #[test]
+    pub fn test_rpm_sqlite_database() {
+        // 30 rpm headers in a 512 byte paged database with the table layout of rpm, so the
+        // packages table has interior pages and the third header spans overflow pages.
+        let database = include_bytes!("fixtures/rpmdb.sqlite");
+
+        let blobs = sqlite_blobs(database, &[], "Packages").unwrap();
+        let packages = blobs.iter().filter_map(|blob| parse_rpm_header(blob)).collect::<Vec<Package>>();
+
+        assert_eq!(packages.len(), 30);
+        assert_eq!(packages[0], Package {
+            name: "package0".to_string(),
+            version: "2:1.0.0-1.fc39".to_string(),
+            architecture: Some("x86_64".to_string()),
+            installed_size: Some(0),
+            manager: PackageManager::Rpm,
+        });
+        assert_eq!(packages[1].version, "1.1.0-2.fc39");
+        assert_eq!(packages[1].architecture.as_deref(), Some("noarch"));
+        assert_eq!(packages[1].installed_size, Some(5_000_000_000));
+        assert_eq!(packages[2].name, "package2");
+        assert_eq!(packages[29].name, "package29");
+        assert_eq!(packages[29].installed_size, Some(29000));
+
+        assert!(sqlite_blobs(database, &[], "Installtid").is_none());
+        assert!(sqlite_blobs(b"not a database", &[], "Packages").is_none());
+
+        // the right most child of the interior root page (page 2) pointing back to itself.
+        let mut cyclic = database.to_vec();
+        cyclic[512 + 8..512 + 12].copy_from_slice(&2u32.to_be_bytes());
+        assert!(sqlite_blobs(&cyclic, &[], "Packages").is_none());
+    }
+
+    #[test]
+    pub fn test_rpm_sqlite_database_with_wal() {
+        // the database above in WAL mode, with the WAL of two transactions that weren't
+        // checkpointed: one installs package30 and the next removes package0.
+        let database = include_bytes!("fixtures/rpmdb-wal.sqlite");
+        let wal = include_bytes!("fixtures/rpmdb-wal.sqlite-wal");
+
+        let names = |wal: &[u8]| sqlite_blobs(database, wal, "Packages").unwrap().iter()
+            .filter_map(|blob| parse_rpm_header(blob))
+            .map(|package| package.name)
+            .collect::<Vec<String>>();
+
+        let packages = names(wal);
+        assert_eq!(packages.len(), 30);
+        assert_eq!((packages[0].as_str(), packages[29].as_str()), ("package1", "package30"));
+
+        // without its last frame the second transaction isn't committed.
+        let packages = names(&wal[..wal.len() - (24 + 512)]);
+        assert_eq!((packages.len(), packages[0].as_str(), packages[30].as_str()), (31, "package0", "package30"));
+
+        // a frame with a wrong checksum ends the log.
+        let mut corrupt = wal.to_vec();
+        corrupt[32 + 24 + 100] ^= 0xff;
+        assert_eq!(names(&corrupt).len(), 30);
+        assert_eq!(names(&corrupt)[0], "package0");
+        assert_eq!(names(&[]).len(), 30);
+    }
+}
@@ -0,0 +1,171 @@
+//! PCI devices of /sys/bus/pci/devices on linux, with names from the pci.ids database.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use ids::IdsDatabase;
+use sysfs;
+use super::Error;
+
+pub(crate) const PCI_IDS: [&str; 2] = ["/usr/share/hwdata/pci.ids", "/usr/share/misc/pci.ids"];
+
+/// A device of the PCI bus. The names are None when no pci.ids database is installed or it
+/// doesn't know the ids.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PciDevice {
+    /// The address in "domain:bus:device.function" form, such as "0000:00:02.0".
+    pub address: String,
+    /// The base class, such as 0x02 for network and 0x03 for display controllers.
+    pub class: u8,
+    pub subclass: u8,
+    /// The programming interface, such as 0x30 for XHCI USB controllers.
+    pub prog_if: u8,
+    pub vendor_id: u16,
+    pub device_id: u16,
+    pub subsystem_vendor_id: Option<u16>,
+    pub subsystem_device_id: Option<u16>,
+    pub revision: Option<u8>,
+    /// The kernel driver bound to the device, such as "nvme" or "i915".
+    pub driver: Option<String>,
+    /// None if the IOMMU is disabled.
+    pub iommu_group: Option<u32>,
+    /// None if the device isn't local to any node, the kernel reports -1 then. That's the case on
+    /// some single node systems, and on multi node ones whose firmware has no locality for the device.
+    pub numa_node: Option<u32>,
+    pub class_name: Option<String>,
+    pub vendor_name: Option<String>,
+    pub device_name: Option<String>,
+    pub subsystem_name: Option<String>,
+}
+
+/// Get every device of the PCI bus with its ids, class, driver, IOMMU group and NUMA node, only
+/// for linux. The names are looked up in /usr/share/hwdata/pci.ids or /usr/share/misc/pci.ids
+/// if one of them is installed.
+pub fn pci_devices() -> Result<Vec<PciDevice>, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    read_pci_devices(Path::new("/sys/bus/pci/devices"), IdsDatabase::load(&PCI_IDS).as_ref())
+}
+
+pub(crate) fn read_pci_devices(dir: &Path, database: Option<&IdsDatabase>) -> Result<Vec<PciDevice>, Error> {
+    let mut devices = match fs::read_dir(dir) {
+        Ok(entries) => entries.flatten().filter_map(|entry| read_pci_device(&entry.path(), database)).collect::<Vec<PciDevice>>(),
+        Err(ref error) if error.kind() == io::ErrorKind::NotFound => vec![],
+        Err(error) => return Err(Error::IO(error)),
+    };
+
+    devices.sort_by(|first, second| first.address.cmp(&second.address));
+    Ok(devices)
+}
+
+/// Reads a device directory, the ids are hex numbers such as "0x8086" there and the class is
+/// the class, subclass and prog-if in one number.
+fn read_pci_device(dir: &Path, database: Option<&IdsDatabase>) -> Option<PciDevice> {
+    let hex = |file: &str| sysfs::read_string(dir.join(file)).and_then(|value| u32::from_str_radix(value.trim_start_matches("0x"), 16).ok());
+
+    let class = hex("class")?;
+    let vendor_id = hex("vendor")? as u16;
+    let device_id = hex("device")? as u16;
+    let subsystem_vendor_id = hex("subsystem_vendor").map(|id| id as u16);
+    let subsystem_device_id = hex("subsystem_device").map(|id| id as u16);
+    let (class, subclass, prog_if) = ((class >> 16) as u8, (class >> 8) as u8, class as u8);
+
+    let subsystem_name = match (database, subsystem_vendor_id, subsystem_device_id) {
+        (Some(database), Some(subsystem_vendor), Some(subsystem_device)) => database.subsystem(vendor_id, device_id, subsystem_vendor, subsystem_device).map(String::from),
+        _ => None,
+    };
+
+    Some(PciDevice {
+        address: dir.file_name()?.to_string_lossy().into_owned(),
+        class,
+        subclass,
+        prog_if,
+        vendor_id,
+        device_id,
+        subsystem_vendor_id,
+        subsystem_device_id,
+        revision: hex("revision").map(|revision| revision as u8),
+        driver: sysfs::link_name(dir.join("driver")),
+        iommu_group: sysfs::link_name(dir.join("iommu_group")).and_then(|group| group.parse::<u32>().ok()),
+        // -1 means the device isn't local to any node.
+        numa_node: sysfs::read_string(dir.join("numa_node")).and_then(|node| node.parse::<u32>().ok()),
+        class_name: database.and_then(|database| database.class(class, subclass, prog_if)).map(String::from),
+        vendor_name: database.and_then(|database| database.vendor(vendor_id)).map(String::from),
+        device_name: database.and_then(|database| database.device(vendor_id, device_id)).map(String::from),
+        subsystem_name,
+    })
+}
+
+// the test links the driver and iommu group like sysfs does, with unix symlinks.
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+    use sysfs::TestDir;
+
+    #[test]
+    pub fn test_read_pci_devices() {
+        let dir = TestDir::new("pci");
+        dir.write(&[
+            ("devices/0000:01:00.0/class", "0x010802"),
+            ("devices/0000:01:00.0/vendor", "0x144d"),
+            ("devices/0000:01:00.0/device", "0xa808"),
+            ("devices/0000:01:00.0/subsystem_vendor", "0x144d"),
+            ("devices/0000:01:00.0/subsystem_device", "0xa801"),
+            ("devices/0000:01:00.0/revision", "0x00"),
+            ("devices/0000:01:00.0/numa_node", "1"),
+            ("devices/0000:00:00.0/class", "0x060000"),
+            ("devices/0000:00:00.0/vendor", "0x8086"),
+            ("devices/0000:00:00.0/device", "0x3e0f"),
+            ("devices/0000:00:00.0/numa_node", "-1"),
+        ]);
+        dir.create_dirs(&["drivers/nvme", "iommu_groups/14"]);
+        dir.symlink("../../drivers/nvme", "devices/0000:01:00.0/driver");
+        dir.symlink("../../iommu_groups/14", "devices/0000:01:00.0/iommu_group");
+
+        let database = IdsDatabase::parse("\
+144d  Samsung Electronics Co Ltd
+\ta808  NVMe SSD Controller SM981/PM981/PM983
+\t\t144d a801  SSD 970 EVO Plus 1TB
+C 01  Mass storage controller
+\t08  Non-Volatile memory controller
+\t\t02  NVM Express
+C 06  Bridge
+\t00  Host bridge
+");
+
+        let devices = read_pci_devices(&dir.join("devices"), Some(&database)).unwrap();
+        let without_names = read_pci_devices(&dir.join("devices"), None).unwrap();
+
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[1], PciDevice {
+            address: "0000:01:00.0".to_string(),
+            class: 0x01,
+            subclass: 0x08,
+            prog_if: 0x02,
+            vendor_id: 0x144d,
+            device_id: 0xa808,
+            subsystem_vendor_id: Some(0x144d),
+            subsystem_device_id: Some(0xa801),
+            revision: Some(0),
+            driver: Some("nvme".to_string()),
+            iommu_group: Some(14),
+            numa_node: Some(1),
+            class_name: Some("NVM Express".to_string()),
+            vendor_name: Some("Samsung Electronics Co Ltd".to_string()),
+            device_name: Some("NVMe SSD Controller SM981/PM981/PM983".to_string()),
+            subsystem_name: Some("SSD 970 EVO Plus 1TB".to_string()),
+        });
+
+        assert_eq!(devices[0].address, "0000:00:00.0");
+        assert_eq!((devices[0].class, devices[0].subclass), (0x06, 0x00));
+        assert_eq!(devices[0].class_name.as_deref(), Some("Host bridge"));
+        assert_eq!((devices[0].vendor_name.as_deref(), devices[0].device_name.as_deref()), (None, None));
+        assert_eq!((devices[0].driver.as_deref(), devices[0].iommu_group, devices[0].numa_node), (None, None, None));
+
+        assert_eq!(without_names[1].vendor_name, None);
+        assert_eq!(without_names[1].driver.as_deref(), Some("nvme"));
+    }
+}
@@ -0,0 +1,233 @@
+//! Batteries, AC adapters and other power supplies, read from /sys/class/power_supply on linux.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+use std::time::Duration;
+
+use sysfs;
+use super::Error;
+
+/// The "type" of a power supply.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PowerSupplyType {
+    Battery,
+    /// The AC adapter.
+    Mains,
+    Usb,
+    Ups,
+    Wireless,
+    Other(String),
+}
+
+/// The charging "status" of a battery.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum PowerSupplyStatus {
+    Charging,
+    Discharging,
+    /// Plugged in but not charging, such as when a charge limit is reached.
+    NotCharging,
+    Full,
+    Unknown,
+}
+
+/// One entry of /sys/class/power_supply. Everything a driver doesn't report is None.
+///
+/// Batteries report either energy (Wh) or charge (Ah) values depending on the driver.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PowerSupply {
+    /// Such as "BAT0", "AC" or "ucsi-source-psy-USBC000:001".
+    pub name: String,
+    pub supply_type: PowerSupplyType,
+    /// "System" for the batteries and adapters of the computer, "Device" for the batteries of
+    /// peripherals such as wireless mice.
+    pub scope: Option<String>,
+    /// Whether an adapter is plugged in.
+    pub online: Option<bool>,
+    pub status: Option<PowerSupplyStatus>,
+    /// Charge level in percent.
+    pub capacity: Option<u8>,
+    /// In Wh.
+    pub energy_now: Option<f64>,
+    pub energy_full: Option<f64>,
+    pub energy_full_design: Option<f64>,
+    /// In Ah.
+    pub charge_now: Option<f64>,
+    pub charge_full: Option<f64>,
+    pub charge_full_design: Option<f64>,
+    /// In V.
+    pub voltage: Option<f64>,
+    /// In A.
+    pub current: Option<f64>,
+    /// Power draw (or charging power) in W.
+    pub power: Option<f64>,
+    /// Some drivers report 0 when they don't know it.
+    pub cycle_count: Option<u32>,
+    /// Such as "Good", "Overheat" or "Dead".
+    pub health: Option<String>,
+    /// Such as "Li-ion" or "Li-poly".
+    pub technology: Option<String>,
+    pub manufacturer: Option<String>,
+    pub model_name: Option<String>,
+    /// Estimated time until the battery is empty, only while discharging.
+    pub time_to_empty: Option<Duration>,
+    /// Estimated time until the battery is full, only while charging.
+    pub time_to_full: Option<Duration>,
+}
+
+impl PowerSupply {
+    /// Checks if it's a battery that powers the computer rather than a peripheral.
+    pub fn is_system_battery(&self) -> bool {
+        self.supply_type == PowerSupplyType::Battery && self.scope.as_deref() != Some("Device")
+    }
+
+    /// Full capacity relative to the design capacity in percent, the wear level of a battery.
+    pub fn health_percent(&self) -> Option<f64> {
+        let (full, design) = match (self.energy_full, self.energy_full_design, self.charge_full, self.charge_full_design) {
+            (Some(full), Some(design), _, _) | (_, _, Some(full), Some(design)) => (full, design),
+            _ => return None,
+        };
+
+        if design > 0.0 { Some(full / design * 100.0) } else { None }
+    }
+}
+
+/// Get every power supply of /sys/class/power_supply: batteries, AC adapters, USB and UPS
+/// supplies, only for linux. Desktops and servers often have none.
+pub fn power_supplies() -> Result<Vec<PowerSupply>, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    let mut supplies = match fs::read_dir("/sys/class/power_supply") {
+        Ok(entries) => entries.flatten().map(|entry| read_power_supply(&entry.path())).collect::<Vec<PowerSupply>>(),
+        Err(ref error) if error.kind() == io::ErrorKind::NotFound => vec![],
+        Err(error) => return Err(Error::IO(error)),
+    };
+
+    supplies.sort_by(|first, second| first.name.cmp(&second.name));
+    Ok(supplies)
+}
+
+/// Reads a power supply directory, see
+/// https://www.kernel.org/doc/Documentation/ABI/testing/sysfs-class-power. Energy, charge,
+/// voltage, current and power values are in µWh, µAh, µV, µA and µW there.
+fn read_power_supply(dir: &Path) -> PowerSupply {
+    let string = |file: &str| sysfs::read_string(dir.join(file)).filter(|value| !value.is_empty());
+    // some drivers report negative currents and powers while discharging.
+    let micro = |file: &str| string(file).and_then(|value| value.parse::<i64>().ok()).map(|value| value.abs() as f64 / 1_000_000.0);
+
+    let supply_type = match string("type").as_deref() {
+        Some("Battery") => PowerSupplyType::Battery,
+        Some("Mains") => PowerSupplyType::Mains,
+        Some("UPS") => PowerSupplyType::Ups,
+        Some("Wireless") => PowerSupplyType::Wireless,
+        Some(usb) if usb.starts_with("USB") => PowerSupplyType::Usb,
+        other => PowerSupplyType::Other(other.unwrap_or_default().to_string()),
+    };
+
+    let status = string("status").map(|status| match status.as_str() {
+        "Charging" => PowerSupplyStatus::Charging,
+        "Discharging" => PowerSupplyStatus::Discharging,
+        "Not charging" => PowerSupplyStatus::NotCharging,
+        "Full" => PowerSupplyStatus::Full,
+        _ => PowerSupplyStatus::Unknown,
+    });
+
+    let voltage = micro("voltage_now");
+    let current = micro("current_now");
+    let power = micro("power_now").or_else(|| Some(voltage? * current?));
+
+    let mut supply = PowerSupply {
+        name: dir.file_name().map(|name| name.to_string_lossy().into_owned()).unwrap_or_default(),
+        supply_type,
+        scope: string("scope"),
+        online: string("online").map(|online| online != "0"),
+        status,
+        capacity: string("capacity").and_then(|capacity| capacity.parse::<u8>().ok()),
+        energy_now: micro("energy_now"),
+        energy_full: micro("energy_full"),
+        energy_full_design: micro("energy_full_design"),
+        charge_now: micro("charge_now"),
+        charge_full: micro("charge_full"),
+        charge_full_design: micro("charge_full_design"),
+        voltage,
+        current,
+        power,
+        cycle_count: string("cycle_count").and_then(|count| count.parse::<u32>().ok()),
+        health: string("health"),
+        technology: string("technology"),
+        manufacturer: string("manufacturer"),
+        model_name: string("model_name"),
+        time_to_empty: None,
+        time_to_full: None,
+    };
+
+    let seconds = |file: &str| string(file).and_then(|value| value.parse::<u64>().ok()).map(Duration::from_secs);
+    match supply.status {
+        Some(PowerSupplyStatus::Discharging) => {
+            supply.time_to_empty = seconds("time_to_empty_now").or_else(|| estimate(supply.energy_now, supply.power).or_else(|| estimate(supply.charge_now, supply.current)));
+        },
+        Some(PowerSupplyStatus::Charging) => {
+            let energy_left = supply.energy_full.and_then(|full| Some(full - supply.energy_now?));
+            let charge_left = supply.charge_full.and_then(|full| Some(full - supply.charge_now?));
+            supply.time_to_full = seconds("time_to_full_now").or_else(|| estimate(energy_left, supply.power).or_else(|| estimate(charge_left, supply.current)));
+        },
+        _ => (),
+    }
+
+    supply
+}
+
+/// Time to drain or fill an amount (Wh or Ah) at a rate (W or A).
+fn estimate(amount: Option<f64>, rate: Option<f64>) -> Option<Duration> {
+    match (amount, rate) {
+        (Some(amount), Some(rate)) if amount >= 0.0 && rate > 0.0 => Some(Duration::from_secs_f64(amount / rate * 3600.0)),
+        _ => None,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sysfs::TestDir;
+
+    #[test]
+    pub fn test_read_power_supply() {
+        let dir = TestDir::new("power");
+        dir.write(&[
+            ("BAT0/type", "Battery"),
+            ("BAT0/scope", "System"),
+            ("BAT0/status", "Discharging"),
+            ("BAT0/capacity", "50"),
+            ("BAT0/energy_now", "25000000"),
+            ("BAT0/energy_full", "50000000"),
+            ("BAT0/energy_full_design", "62500000"),
+            ("BAT0/power_now", "12500000"),
+            ("BAT0/voltage_now", "12000000"),
+            ("BAT0/cycle_count", "321"),
+            ("BAT0/technology", "Li-ion"),
+            ("AC/type", "Mains"),
+            ("AC/online", "0"),
+        ]);
+
+        let battery = read_power_supply(&dir.join("BAT0"));
+        let adapter = read_power_supply(&dir.join("AC"));
+
+        assert_eq!(battery.name, "BAT0");
+        assert!(battery.is_system_battery());
+        assert_eq!(battery.status, Some(PowerSupplyStatus::Discharging));
+        assert_eq!(battery.capacity, Some(50));
+        assert_eq!((battery.energy_now, battery.energy_full), (Some(25.0), Some(50.0)));
+        assert_eq!(battery.health_percent(), Some(80.0));
+        assert_eq!((battery.power, battery.voltage, battery.current), (Some(12.5), Some(12.0), None));
+        assert_eq!(battery.cycle_count, Some(321));
+        assert_eq!(battery.time_to_empty, Some(Duration::from_secs(7200)));
+        assert_eq!(battery.time_to_full, None);
+
+        assert_eq!(adapter.supply_type, PowerSupplyType::Mains);
+        assert_eq!(adapter.online, Some(false));
+        assert!(!adapter.is_system_battery());
+        assert_eq!(adapter.health_percent(), None);
+    }
+}
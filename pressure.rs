@@ -0,0 +1,142 @@
+//! Pressure stall information (PSI) of the system and of cgroup v2 groups on linux.
+
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use cgroup::cgroup2_dir;
+use super::Error;
+
+/// The share of time that tasks were stalled on a resource, see
+/// <https://docs.kernel.org/accounting/psi.html>.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct PressureValues {
+    /// Percentage of the last 10 seconds.
+    pub avg10: f64,
+    /// Percentage of the last 60 seconds.
+    pub avg60: f64,
+    /// Percentage of the last 300 seconds.
+    pub avg300: f64,
+    /// Total stall time in microseconds since boot, the difference of two readings gives the stall time between them.
+    pub total: u64,
+}
+
+/// Pressure of one resource.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct Pressure {
+    /// Time that at least one task was stalled. irq has no "some" line.
+    pub some: Option<PressureValues>,
+    /// Time that all non idle tasks were stalled at the same time. The system wide cpu
+    /// pressure only has it since linux 5.13, where it's always zero.
+    pub full: Option<PressureValues>,
+}
+
+/// Pressure of every resource of the system or of a cgroup.
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct SystemPressure {
+    pub cpu: Pressure,
+    pub memory: Pressure,
+    pub io: Pressure,
+    /// Only available since linux 6.1 with CONFIG_IRQ_TIME_ACCOUNTING.
+    pub irq: Option<Pressure>,
+}
+
+/// Get the system wide pressure stall information from /proc/pressure, only for linux.
+///
+/// It's a better sign of cpu, memory and io contention than `loadavg()`. Returns
+/// `Error::UnsupportedSystem` if the kernel has no PSI: it's older than 4.20, it's built without
+/// CONFIG_PSI or PSI is disabled with the psi=0 boot option.
+pub fn pressure() -> Result<SystemPressure, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    read_pressures(|resource| Path::new("/proc/pressure").join(resource))
+}
+
+/// Get the pressure stall information of a cgroup v2 group from its `*.pressure` files, only for linux.
+///
+/// The group path is relative to the root of the cgroup v2 hierarchy, like "/system.slice/docker.service"
+/// in /proc/self/cgroup. If it's None, the group of the current process is used. Returns
+/// `Error::UnsupportedSystem` if PSI is disabled, see `pressure()`.
+pub fn cgroup_pressure(cgroup: Option<&str>) -> Result<SystemPressure, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    let dir = cgroup2_dir(cgroup)?;
+    if !dir.is_dir() {
+        return Err(Error::IO(io::Error::new(io::ErrorKind::NotFound, format!("{} is not found", dir.display()))));
+    }
+
+    read_pressures(|resource| dir.join(format!("{}.pressure", resource)))
+}
+
+fn read_pressures<F: Fn(&str) -> PathBuf>(path_of: F) -> Result<SystemPressure, Error> {
+    let required = |resource: &str| read_pressure(&path_of(resource))?.ok_or(Error::UnsupportedSystem);
+
+    Ok(SystemPressure {
+        cpu: required("cpu")?,
+        memory: required("memory")?,
+        io: required("io")?,
+        irq: read_pressure(&path_of("irq"))?,
+    })
+}
+
+/// Reads a pressure file, None if it doesn't exist. With psi=0 the files exist but reading them
+/// fails with EOPNOTSUPP.
+fn read_pressure(path: &Path) -> Result<Option<Pressure>, Error> {
+    match fs::read_to_string(path) {
+        Ok(content) => Ok(Some(parse_pressure(&content))),
+        Err(ref error) if error.kind() == io::ErrorKind::NotFound => Ok(None),
+        Err(ref error) if error.raw_os_error() == Some(libc::EOPNOTSUPP) => Err(Error::UnsupportedSystem),
+        Err(error) => Err(Error::IO(error)),
+    }
+}
+
+/// Parses lines such as "some avg10=0.12 avg60=0.05 avg300=0.01 total=123456".
+fn parse_pressure(s: &str) -> Pressure {
+    let mut pressure = Pressure::default();
+
+    for line in s.lines() {
+        let mut fields = line.split_whitespace();
+        let kind = fields.next();
+        let mut values = PressureValues::default();
+
+        for field in fields {
+            match field.split_once('=') {
+                Some(("avg10", value)) => values.avg10 = value.parse().unwrap_or(0.0),
+                Some(("avg60", value)) => values.avg60 = value.parse().unwrap_or(0.0),
+                Some(("avg300", value)) => values.avg300 = value.parse().unwrap_or(0.0),
+                Some(("total", value)) => values.total = value.parse().unwrap_or(0),
+                _ => (),
+            }
+        }
+
+        match kind {
+            Some("some") => pressure.some = Some(values),
+            Some("full") => pressure.full = Some(values),
+            _ => (),
+        }
+    }
+
+    pressure
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_parse_pressure() {
+        let pressure = parse_pressure("some avg10=1.95 avg60=3.85 avg300=2.60 total=34373125\nfull avg10=0.00 avg60=0.10 avg300=0.00 total=2918607\n");
+
+        assert_eq!(pressure.some, Some(PressureValues { avg10: 1.95, avg60: 3.85, avg300: 2.60, total: 34373125 }));
+        assert_eq!(pressure.full.unwrap().avg60, 0.10);
+        assert_eq!(pressure.full.unwrap().total, 2918607);
+
+        let irq = parse_pressure("full avg10=0.00 avg60=0.00 avg300=0.00 total=1024\n");
+        assert_eq!(irq.some, None);
+        assert_eq!(irq.full.unwrap().total, 1024);
+    }
+}
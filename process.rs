@@ -0,0 +1,684 @@
+//! Process enumeration and inspection, read from /proc on linux.
+
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::PathBuf;
+
+use super::Error;
+
+/// Scheduling state of a process, the third field of `/proc/[pid]/stat`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ProcessState {
+    Running,
+    Sleeping,
+    /// Uninterruptible sleep, usually waiting for io.
+    DiskSleep,
+    Zombie,
+    Stopped,
+    TracingStop,
+    Dead,
+    /// Idle kernel thread.
+    Idle,
+    Unknown(char),
+}
+
+impl ProcessState {
+    fn from_char(state: char) -> ProcessState {
+        match state {
+            'R' => ProcessState::Running,
+            'S' => ProcessState::Sleeping,
+            'D' => ProcessState::DiskSleep,
+            'Z' => ProcessState::Zombie,
+            'T' => ProcessState::Stopped,
+            't' => ProcessState::TracingStop,
+            'X' | 'x' => ProcessState::Dead,
+            'I' => ProcessState::Idle,
+            other => ProcessState::Unknown(other),
+        }
+    }
+}
+
+/// Basic information of a process, read from `/proc/[pid]/stat` and `/proc/[pid]/status`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ProcessInfo {
+    pub pid: u32,
+    /// Parent pid, 0 for init and kernel threads started by the kernel itself.
+    pub ppid: u32,
+    /// Command name, truncated to 15 characters by the kernel.
+    pub name: String,
+    pub state: ProcessState,
+    /// Real user id.
+    pub uid: u32,
+    /// Real group id.
+    pub gid: u32,
+    /// Start time of the process, in seconds since the unix epoch.
+    pub start_time: u64,
+    pub threads: u64,
+    /// Resident set size in bytes.
+    pub rss: u64,
+    /// Virtual memory size in bytes.
+    pub virtual_size: u64,
+    pub nice: i32,
+    /// Time spent in user mode, in clock ticks (`sysconf(_SC_CLK_TCK)`, usually 1/100 second).
+    pub user_time: u64,
+    /// Time spent in kernel mode, in clock ticks.
+    pub system_time: u64,
+}
+
+/// Iterator over the processes that `processes()` returns.
+///
+/// Processes that exit while the iterator runs are skipped.
+#[derive(Debug)]
+pub struct Processes {
+    entries: fs::ReadDir,
+    boot_time: u64,
+}
+
+impl Iterator for Processes {
+    type Item = ProcessInfo;
+
+    fn next(&mut self) -> Option<ProcessInfo> {
+        for entry in self.entries.by_ref().flatten() {
+            let pid = match entry.file_name().to_string_lossy().parse::<u32>() {
+                Ok(pid) => pid,
+                Err(_) => continue,
+            };
+
+            if let Ok(info) = read_process_info(pid, self.boot_time) {
+                return Some(info);
+            }
+        }
+
+        None
+    }
+}
+
+/// Get an iterator over every process on the system, only for linux.
+///
+/// ```no_run
+/// for process in sys_info_extended::processes().unwrap() {
+///     println!("{} {} {:?}", process.pid, process.name, process.state);
+/// }
+/// ```
+pub fn processes() -> Result<Processes, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    Ok(Processes {
+        entries: fs::read_dir("/proc")?,
+        boot_time: boot_time()?,
+    })
+}
+
+/// Boot time of the system in seconds since the unix epoch, the "btime" line of /proc/stat.
+pub(crate) fn boot_time() -> Result<u64, Error> {
+    fs::read_to_string("/proc/stat")?
+        .lines()
+        .find(|line| line.starts_with("btime "))
+        .and_then(|line| line[6..].trim().parse::<u64>().ok())
+        .ok_or(Error::Unknown)
+}
+
+pub(crate) fn read_process_info(pid: u32, boot_time: u64) -> Result<ProcessInfo, Error> {
+    let stat = fs::read_to_string(format!("/proc/{}/stat", pid))?;
+    let status = fs::read_to_string(format!("/proc/{}/status", pid))?;
+
+    let mut info = parse_stat(&stat, boot_time, clock_ticks(), page_size())
+        .ok_or_else(|| Error::IO(io::Error::new(io::ErrorKind::InvalidData, format!("unexpected /proc/{}/stat format", pid))))?;
+
+    for line in status.lines() {
+        let id = |line: &str| line.split_whitespace().nth(1).and_then(|id| id.parse::<u32>().ok()).unwrap_or(0);
+
+        if line.starts_with("Uid:") {
+            info.uid = id(line);
+        } else if line.starts_with("Gid:") {
+            info.gid = id(line);
+        }
+    }
+
+    Ok(info)
+}
+
+fn parse_stat(stat: &str, boot_time: u64, clock_ticks: u64, page_size: u64) -> Option<ProcessInfo> {
+    // the command name is in parentheses and can contain spaces and parentheses itself.
+    let name_start = stat.find('(')?;
+    let name_end = stat.rfind(')')?;
+    let fields = stat[name_end + 1..].split_whitespace().collect::<Vec<&str>>();
+    if fields.len() < 22 {
+        return None;
+    }
+
+    let number = |index: usize| fields[index].parse::<u64>().unwrap_or(0);
+
+    Some(ProcessInfo {
+        pid: stat[..name_start].trim().parse().ok()?,
+        ppid: number(1) as u32,
+        name: stat[name_start + 1..name_end].to_string(),
+        state: ProcessState::from_char(fields[0].chars().next().unwrap_or('?')),
+        uid: 0,
+        gid: 0,
+        start_time: boot_time + number(19) / clock_ticks.max(1),
+        threads: number(17),
+        rss: number(21) * page_size,
+        virtual_size: number(20),
+        nice: fields[16].parse().unwrap_or(0),
+        user_time: number(11),
+        system_time: number(12),
+    })
+}
+
+/// What an open file descriptor points to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FdTarget {
+    /// A file, directory or device.
+    Path(PathBuf),
+    /// A socket and its inode number, which can be found in /proc/net/tcp, udp, unix and so on.
+    Socket(u64),
+    /// A pipe and its inode number, both ends of a pipe have the same inode.
+    Pipe(u64),
+    /// An anonymous inode, such as `[eventfd]`, `[eventpoll]` or `inotify`.
+    AnonInode(String),
+    /// Any other link target.
+    Other(String),
+}
+
+/// An open file descriptor of a process.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FileDescriptor {
+    pub fd: u32,
+    pub target: FdTarget,
+}
+
+/// A resource limit of a process, a line of `/proc/[pid]/limits`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ResourceLimit {
+    /// Such as "Max open files".
+    pub name: String,
+    /// `None` means unlimited.
+    pub soft: Option<u64>,
+    /// `None` means unlimited.
+    pub hard: Option<u64>,
+    /// Such as "seconds", "bytes" or "files", empty for limits without a unit.
+    pub units: String,
+}
+
+/// Detailed information of a single process.
+///
+/// Most of these files can only be read by the owner of the process or root, so every field
+/// has its own result: a permission error on one of them doesn't hide the others.
+#[derive(Debug)]
+pub struct ProcessDetails {
+    pub info: ProcessInfo,
+    /// Arguments of the command line, the first one is usually the program. Empty for kernel threads.
+    pub cmdline: Result<Vec<String>, Error>,
+    /// Environment variables that the process started with.
+    pub environ: Result<HashMap<String, String>, Error>,
+    pub cwd: Result<PathBuf, Error>,
+    /// Path of the executable.
+    pub exe: Result<PathBuf, Error>,
+    pub fds: Result<Vec<FileDescriptor>, Error>,
+    pub limits: Result<Vec<ResourceLimit>, Error>,
+    /// Badness score that the oom killer uses, between 0 and 1000.
+    pub oom_score: Result<i32, Error>,
+    /// Adjustment of the oom score, between -1000 and 1000.
+    pub oom_score_adj: Result<i32, Error>,
+    /// Swapped out memory in bytes, the "VmSwap" line of `/proc/[pid]/status`.
+    pub swap: Result<u64, Error>,
+    /// Name of the owner user, or the uid if it has no user.
+    pub user: Result<String, Error>,
+}
+
+/// Get the command line, environment, cwd, executable, open files, limits, oom score, swap usage
+/// and owner of the process with the given pid, only for linux.
+///
+/// Fails only if the process doesn't exist, errors of the single fields are kept in the fields.
+pub fn process(pid: u32) -> Result<ProcessDetails, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    let info = read_process_info(pid, boot_time()?)?;
+    let base = PathBuf::from(format!("/proc/{}", pid));
+
+    let cmdline = fs::read(base.join("cmdline")).map(split_nul).map_err(Error::from);
+    let environ = fs::read(base.join("environ")).map_err(Error::from).map(|bytes| {
+        split_nul(bytes).into_iter()
+            .filter_map(|variable| {
+                let mut split_variable = variable.splitn(2, '=');
+                Some((split_variable.next()?.to_string(), split_variable.next()?.to_string()))
+            })
+            .collect()
+    });
+
+    let read_number = |file: &str| -> Result<i32, Error> {
+        fs::read_to_string(base.join(file))?.trim().parse::<i32>().map_err(|error| Error::General(error.to_string()))
+    };
+
+    let swap = fs::read_to_string(base.join("status")).map_err(Error::from).map(|status| {
+        status.lines()
+            .find(|line| line.starts_with("VmSwap:"))
+            .and_then(|line| line.split_whitespace().nth(1))
+            .and_then(|kb| kb.parse::<u64>().ok())
+            .map(|kb| kb * 1024)
+            .unwrap_or(0)
+    });
+
+    Ok(ProcessDetails {
+        cmdline,
+        environ,
+        cwd: fs::read_link(base.join("cwd")).map_err(Error::from),
+        exe: fs::read_link(base.join("exe")).map_err(Error::from),
+        fds: read_fds(&base),
+        limits: fs::read_to_string(base.join("limits")).map(|limits| parse_limits(&limits)).map_err(Error::from),
+        oom_score: read_number("oom_score"),
+        oom_score_adj: read_number("oom_score_adj"),
+        swap,
+        user: user_name(info.uid),
+        info,
+    })
+}
+
+/// Splits the NUL terminated strings of `/proc/<pid>/cmdline` and `environ`. Empty strings
+/// are kept, they are empty arguments, only the file of a kernel thread is empty as a whole.
+fn split_nul(bytes: Vec<u8>) -> Vec<String> {
+    if bytes.is_empty() {
+        return vec![];
+    }
+
+    bytes.strip_suffix(&[0]).unwrap_or(&bytes)
+        .split(|byte| *byte == 0)
+        .map(|part| String::from_utf8_lossy(part).into_owned())
+        .collect()
+}
+
+fn read_fds(base: &std::path::Path) -> Result<Vec<FileDescriptor>, Error> {
+    let mut fds = vec![];
+
+    for entry in fs::read_dir(base.join("fd"))?.flatten() {
+        let fd = match entry.file_name().to_string_lossy().parse::<u32>() {
+            Ok(fd) => fd,
+            Err(_) => continue,
+        };
+
+        // the descriptor may be closed since the directory was read.
+        if let Ok(target) = fs::read_link(entry.path()) {
+            fds.push(FileDescriptor { fd, target: parse_fd_target(target) });
+        }
+    }
+
+    fds.sort_by_key(|fd| fd.fd);
+    Ok(fds)
+}
+
+fn parse_fd_target(target: PathBuf) -> FdTarget {
+    if target.is_absolute() {
+        return FdTarget::Path(target);
+    }
+
+    let target = target.to_string_lossy().into_owned();
+    let inode = |prefix: &str| target.strip_prefix(prefix)
+        .and_then(|rest| rest.strip_suffix(']'))
+        .and_then(|inode| inode.parse::<u64>().ok());
+
+    if let Some(inode) = inode("socket:[") {
+        FdTarget::Socket(inode)
+    } else if let Some(inode) = inode("pipe:[") {
+        FdTarget::Pipe(inode)
+    } else if let Some(name) = target.strip_prefix("anon_inode:") {
+        FdTarget::AnonInode(name.to_string())
+    } else {
+        FdTarget::Other(target)
+    }
+}
+
+fn parse_limits(s: &str) -> Vec<ResourceLimit> {
+    let mut limits = vec![];
+
+    // the name column is 26 characters wide and the name itself has spaces.
+    for line in s.lines().skip(1) {
+        if line.len() <= 26 || !line.is_char_boundary(26) {
+            continue;
+        }
+
+        let mut values = line[26..].split_whitespace();
+        let value = |value: Option<&str>| value.and_then(|value| value.parse::<u64>().ok());
+
+        limits.push(ResourceLimit {
+            name: line[..26].trim().to_string(),
+            soft: value(values.next()),
+            hard: value(values.next()),
+            units: values.next().unwrap_or("").to_string(),
+        });
+    }
+
+    limits
+}
+
+/// Looks the user name of the given uid up with getpwuid_r, so users of LDAP, sssd and
+/// systemd-homed are found too. Uids without a user yield the uid itself, like `ps` does.
+#[cfg(unix)]
+fn user_name(uid: u32) -> Result<String, Error> {
+    use std::ffi::CStr;
+    use std::mem;
+    use std::ptr;
+
+    let mut buffer = vec![0 as libc::c_char; 1024];
+
+    loop {
+        let mut passwd: libc::passwd = unsafe { mem::zeroed() };
+        let mut result: *mut libc::passwd = ptr::null_mut();
+        let error = unsafe { libc::getpwuid_r(uid as libc::uid_t, &mut passwd, buffer.as_mut_ptr(), buffer.len(), &mut result) };
+
+        // the entry doesn't fit the buffer.
+        if error == libc::ERANGE && buffer.len() < 1024 * 1024 {
+            buffer.resize(buffer.len() * 2, 0);
+            continue;
+        }
+
+        if error != 0 {
+            return Err(Error::IO(io::Error::from_raw_os_error(error)));
+        }
+
+        if result.is_null() || passwd.pw_name.is_null() {
+            return Ok(uid.to_string());
+        }
+
+        return Ok(unsafe { CStr::from_ptr(passwd.pw_name) }.to_string_lossy().into_owned());
+    }
+}
+
+#[cfg(not(unix))]
+fn user_name(uid: u32) -> Result<String, Error> {
+    Ok(uid.to_string())
+}
+
+/// Summed resource usage of a process and all of its descendants.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct SubtreeUsage {
+    pub processes: usize,
+    pub threads: u64,
+    /// Resident set size in bytes. Shared pages are counted once per process.
+    pub rss: u64,
+    pub virtual_size: u64,
+    /// User and system cpu time, in clock ticks.
+    pub cpu_time: u64,
+}
+
+/// Parent/child relations of the processes at the time of a scan.
+///
+/// ```no_run
+/// let tree = sys_info_extended::process_tree().unwrap();
+/// println!("{}", tree.render(1));
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct ProcessTree {
+    processes: HashMap<u32, ProcessInfo>,
+    children: HashMap<u32, Vec<u32>>,
+}
+
+impl ProcessTree {
+    /// Builds a tree from already collected processes.
+    pub fn from_processes<I: IntoIterator<Item = ProcessInfo>>(processes: I) -> ProcessTree {
+        let processes = processes.into_iter().map(|process| (process.pid, process)).collect::<HashMap<u32, ProcessInfo>>();
+        let mut children: HashMap<u32, Vec<u32>> = HashMap::new();
+
+        for process in processes.values() {
+            if process.ppid != process.pid {
+                children.entry(process.ppid).or_default().push(process.pid);
+            }
+        }
+
+        for pids in children.values_mut() {
+            pids.sort_unstable();
+        }
+
+        ProcessTree { processes, children }
+    }
+
+    /// The process with the given pid, if it was running at the time of the scan.
+    pub fn get(&self, pid: u32) -> Option<&ProcessInfo> {
+        self.processes.get(&pid)
+    }
+
+    /// Quantity of processes in the tree.
+    pub fn len(&self) -> usize {
+        self.processes.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.processes.is_empty()
+    }
+
+    /// Processes whose parent isn't in the tree, usually init (1) and kthreadd (2), sorted by pid.
+    pub fn roots(&self) -> Vec<&ProcessInfo> {
+        let mut roots = self.processes.values()
+            .filter(|process| process.ppid == process.pid || !self.processes.contains_key(&process.ppid))
+            .collect::<Vec<&ProcessInfo>>();
+
+        roots.sort_by_key(|process| process.pid);
+        roots
+    }
+
+    /// Direct children of the given process, sorted by pid.
+    pub fn children(&self, pid: u32) -> Vec<&ProcessInfo> {
+        self.children.get(&pid)
+            .map(|pids| pids.iter().filter_map(|child| self.processes.get(child)).collect())
+            .unwrap_or_default()
+    }
+
+    /// Children, grandchildren and so on of the given process, in depth first order.
+    pub fn descendants(&self, pid: u32) -> Vec<&ProcessInfo> {
+        let mut descendants = vec![];
+        let mut seen = HashSet::new();
+        seen.insert(pid);
+
+        let mut stack = self.children(pid);
+        stack.reverse();
+
+        while let Some(process) = stack.pop() {
+            // pids that were reused during the scan can link the parents into a cycle.
+            if !seen.insert(process.pid) {
+                continue;
+            }
+
+            descendants.push(process);
+
+            let mut children = self.children(process.pid);
+            children.reverse();
+            stack.extend(children);
+        }
+
+        descendants
+    }
+
+    /// Parent, grandparent and so on of the given process, up to the root of its tree.
+    pub fn ancestors(&self, pid: u32) -> Vec<&ProcessInfo> {
+        let mut ancestors = vec![];
+        let mut seen = HashSet::new();
+        seen.insert(pid);
+
+        let mut current = self.processes.get(&pid);
+        while let Some(parent) = current.and_then(|process| self.processes.get(&process.ppid)) {
+            if !seen.insert(parent.pid) {
+                break;
+            }
+
+            ancestors.push(parent);
+            current = Some(parent);
+        }
+
+        ancestors
+    }
+
+    /// Sums the usage of the given process and all of its descendants.
+    pub fn subtree_usage(&self, pid: u32) -> Option<SubtreeUsage> {
+        let root = self.processes.get(&pid)?;
+
+        Some(std::iter::once(root).chain(self.descendants(pid)).fold(SubtreeUsage::default(), |usage, process| SubtreeUsage {
+            processes: usage.processes + 1,
+            threads: usage.threads + process.threads,
+            rss: usage.rss + process.rss,
+            virtual_size: usage.virtual_size + process.virtual_size,
+            cpu_time: usage.cpu_time + process.user_time + process.system_time,
+        }))
+    }
+
+    /// Draws the subtree of the given process like `pstree -p` does, one "name(pid)" per line.
+    pub fn render(&self, pid: u32) -> String {
+        let mut output = String::new();
+
+        if let Some(process) = self.processes.get(&pid) {
+            output.push_str(&format!("{}({})\n", process.name, process.pid));
+            self.render_children(pid, "", &mut HashSet::from([pid]), &mut output);
+        }
+
+        output
+    }
+
+    fn render_children(&self, pid: u32, prefix: &str, seen: &mut HashSet<u32>, output: &mut String) {
+        let children = self.children(pid).into_iter().filter(|child| seen.insert(child.pid)).collect::<Vec<&ProcessInfo>>();
+
+        for (index, child) in children.iter().enumerate() {
+            let last = index + 1 == children.len();
+
+            output.push_str(&format!("{}{}{}({})\n", prefix, if last { "└─" } else { "├─" }, child.name, child.pid));
+            self.render_children(child.pid, &format!("{}{}", prefix, if last { "  " } else { "│ " }), seen, output);
+        }
+    }
+}
+
+impl fmt::Display for ProcessTree {
+    /// Draws every root with its subtree.
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        for root in self.roots() {
+            write!(f, "{}", self.render(root.pid))?;
+        }
+
+        Ok(())
+    }
+}
+
+/// Scans /proc and builds the `ProcessTree` of every process, only for linux.
+pub fn process_tree() -> Result<ProcessTree, Error> {
+    Ok(ProcessTree::from_processes(processes()?))
+}
+
+#[cfg(unix)]
+pub(crate) fn clock_ticks() -> u64 {
+    let ticks = unsafe { libc::sysconf(libc::_SC_CLK_TCK) };
+    if ticks > 0 { ticks as u64 } else { 100 }
+}
+
+#[cfg(not(unix))]
+pub(crate) fn clock_ticks() -> u64 {
+    100
+}
+
+#[cfg(unix)]
+fn page_size() -> u64 {
+    let size = unsafe { libc::sysconf(libc::_SC_PAGESIZE) };
+    if size > 0 { size as u64 } else { 4096 }
+}
+
+#[cfg(not(unix))]
+fn page_size() -> u64 {
+    4096
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_parse_stat() {
+        let stat = "6888 (tmux: server (1)) S 1 6888 6884 0 -1 4194304 82 0 0 0 150 50 0 0 20 -5 3 0 2000 2703360 314 18446744073709551615 0 0";
+        let info = parse_stat(stat, 1_000_000, 100, 4096).unwrap();
+
+        assert_eq!(info.pid, 6888);
+        assert_eq!(info.ppid, 1);
+        assert_eq!(info.name, "tmux: server (1)");
+        assert_eq!(info.state, ProcessState::Sleeping);
+        assert_eq!(info.nice, -5);
+        assert_eq!(info.threads, 3);
+        assert_eq!(info.start_time, 1_000_020);
+        assert_eq!(info.virtual_size, 2703360);
+        assert_eq!(info.rss, 314 * 4096);
+        assert_eq!((info.user_time, info.system_time), (150, 50));
+    }
+
+    #[test]
+    pub fn test_parse_limits() {
+        let limits = parse_limits("Limit                     Soft Limit           Hard Limit           Units     
+Max cpu time              unlimited            unlimited            seconds   
+Max open files            1024                 524288               files     
+Max nice priority         0                    0                    
+");
+        assert_eq!(limits.len(), 3);
+        assert_eq!(limits[0], ResourceLimit { name: "Max cpu time".to_string(), soft: None, hard: None, units: "seconds".to_string() });
+        assert_eq!((limits[1].soft, limits[1].hard), (Some(1024), Some(524288)));
+        assert_eq!(limits[2].units, "");
+    }
+
+    #[test]
+    pub fn test_split_nul() {
+        assert_eq!(split_nul(b"prog\0\0x\0".to_vec()), vec!["prog", "", "x"]);
+        assert_eq!(split_nul(b"prog\0x".to_vec()), vec!["prog", "x"]);
+        assert_eq!(split_nul(b"\0".to_vec()), vec![""]);
+        assert!(split_nul(vec![]).is_empty());
+    }
+
+    #[test]
+    pub fn test_parse_fd_target() {
+        assert_eq!(parse_fd_target(PathBuf::from("/dev/null")), FdTarget::Path(PathBuf::from("/dev/null")));
+        assert_eq!(parse_fd_target(PathBuf::from("socket:[12167]")), FdTarget::Socket(12167));
+        assert_eq!(parse_fd_target(PathBuf::from("pipe:[42]")), FdTarget::Pipe(42));
+        assert_eq!(parse_fd_target(PathBuf::from("anon_inode:[eventfd]")), FdTarget::AnonInode("[eventfd]".to_string()));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    pub fn test_user_name() {
+        assert_eq!(user_name(0).unwrap(), "root");
+        assert_eq!(user_name(3_999_999_999).unwrap(), "3999999999");
+    }
+
+    #[test]
+    pub fn test_process_tree() {
+        let process = |pid: u32, ppid: u32, name: &str| ProcessInfo {
+            pid, ppid,
+            name: name.to_string(),
+            state: ProcessState::Sleeping,
+            uid: 0, gid: 0, start_time: 0,
+            threads: 1, rss: 100, virtual_size: 1000, nice: 0,
+            user_time: 2, system_time: 1,
+        };
+        let tree = ProcessTree::from_processes(vec![
+            process(1, 0, "init"),
+            process(10, 1, "sshd"),
+            process(11, 10, "sshd"),
+            process(12, 11, "bash"),
+            process(20, 1, "cron"),
+            process(2, 0, "kthreadd"),
+        ]);
+
+        let pids = |processes: Vec<&ProcessInfo>| processes.iter().map(|process| process.pid).collect::<Vec<u32>>();
+        assert_eq!(pids(tree.roots()), vec![1, 2]);
+        assert_eq!(pids(tree.children(1)), vec![10, 20]);
+        assert_eq!(pids(tree.descendants(1)), vec![10, 11, 12, 20]);
+        assert_eq!(pids(tree.ancestors(12)), vec![11, 10, 1]);
+
+        let usage = tree.subtree_usage(10).unwrap();
+        assert_eq!((usage.processes, usage.rss, usage.cpu_time), (3, 300, 9));
+
+        assert_eq!(tree.render(1), "init(1)\n├─sshd(10)\n│ └─sshd(11)\n│   └─bash(12)\n└─cron(20)\n");
+
+        // a reused pid made 30 and 31 each other's parent.
+        let cycle = ProcessTree::from_processes(vec![process(1, 0, "init"), process(30, 31, "a"), process(31, 30, "b")]);
+        assert_eq!(pids(cycle.descendants(30)), vec![31]);
+        assert_eq!(pids(cycle.ancestors(30)), vec![31]);
+        assert_eq!(cycle.render(30), "a(30)\n└─b(31)\n");
+    }
+}
@@ -0,0 +1,359 @@
+//! Finding installed programs without running them, and detecting their versions.
+
+use std::env;
+use std::ffi::OsString;
+use std::fmt;
+use std::io::Read;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use std::sync::mpsc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+use super::Error;
+
+/// Arguments that are tried in order to make a program print its version. It's the list that
+/// `is_program_installed()` used to run programs with, without the bare invocation since that
+/// starts interactive programs. The bare "version" is tried last, since it's a subcommand or a
+/// file name for many programs.
+const VERSION_PROBES: &[&[&str]] = &[&["--version"], &["-version"], &["-v"], &["version"]];
+
+/// Programs whose version argument is known, so the probes don't have to be tried.
+const KNOWN_VERSION_ARGS: &[(&str, &[&str])] = &[
+    ("go", &["version"]),
+    ("java", &["-version"]),
+    ("javac", &["-version"]),
+    ("openssl", &["version"]),
+    ("ssh", &["-V"]),
+    ("kubectl", &["version", "--client"]),
+    ("helm", &["version", "--short"]),
+    ("dotnet", &["--version"]),
+    ("unzip", &["-v"]),
+    ("zip", &["-v"]),
+];
+
+/// Default time limit of a single version probe in `program_version()`.
+const VERSION_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// Get every executable with the given name in the directories of the PATH env, in PATH order.
+///
+/// On unix a file only counts if it has an executable permission bit. On windows the
+/// extensions of the PATHEXT env (".COM;.EXE;.BAT;.CMD" if it's not set) are tried, unless
+/// the name already has one of them. If the name contains a path separator it's checked as
+/// is instead of being searched. Relative paths are made absolute against the current directory,
+/// symlinks aren't resolved. Works on both Windows and Linux.
+pub fn which(program: &str) -> Vec<PathBuf> {
+    if program.is_empty() {
+        return vec![];
+    }
+
+    let candidates = candidate_names(program);
+
+    if program.contains('/') || (cfg!(windows) && program.contains('\\')) {
+        return candidates.into_iter()
+            .map(PathBuf::from)
+            .filter(|path| is_executable(path))
+            .map(|path| absolute(&path))
+            .collect();
+    }
+
+    let path_env = env::var_os("PATH").unwrap_or_default();
+    let mut found: Vec<PathBuf> = vec![];
+
+    for dir in env::split_paths(&path_env) {
+        // an empty PATH entry means the current directory.
+        let dir = if dir.as_os_str().is_empty() { PathBuf::from(".") } else { dir };
+
+        for name in candidates.iter() {
+            let path = dir.join(name);
+            if !is_executable(&path) {
+                continue;
+            }
+
+            let path = absolute(&path);
+            if !found.contains(&path) {
+                found.push(path);
+            }
+        }
+    }
+
+    found
+}
+
+/// Get the first executable with the given name in the PATH, the one a shell would run. See `which()`.
+pub fn find_executable(program: &str) -> Option<PathBuf> {
+    which(program).into_iter().next()
+}
+
+/// Joins a relative path to the current directory, the "." components are left out.
+fn absolute(path: &Path) -> PathBuf {
+    if path.is_absolute() {
+        return path.to_path_buf();
+    }
+
+    match env::current_dir() {
+        Ok(dir) => dir.join(path).components().collect(),
+        Err(_) => path.to_path_buf(),
+    }
+}
+
+fn candidate_names(program: &str) -> Vec<OsString> {
+    if !cfg!(windows) {
+        return vec![OsString::from(program)];
+    }
+
+    let pathext = env::var("PATHEXT").unwrap_or_else(|_| ".COM;.EXE;.BAT;.CMD".to_string());
+    let extensions = pathext.split(';').filter(|extension| !extension.is_empty()).collect::<Vec<&str>>();
+    let lowercase_program = program.to_lowercase();
+
+    if extensions.iter().any(|extension| lowercase_program.ends_with(&extension.to_lowercase())) {
+        return vec![OsString::from(program)];
+    }
+
+    extensions.iter().map(|extension| OsString::from(format!("{}{}", program, extension))).collect()
+}
+
+#[cfg(unix)]
+fn is_executable(path: &Path) -> bool {
+    use std::os::unix::fs::PermissionsExt;
+
+    match path.metadata() {
+        Ok(metadata) => metadata.is_file() && metadata.permissions().mode() & 0o111 != 0,
+        Err(_) => false,
+    }
+}
+
+#[cfg(not(unix))]
+fn is_executable(path: &Path) -> bool {
+    path.is_file()
+}
+
+/// A version number such as 2.39.2, compared by major, minor and patch.
+///
+/// Missing parts are zero, so "3.11" equals "3.11.0". Anything after the patch (a fourth
+/// number, "-rc1", "p1", distribution suffixes) is ignored.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Version {
+    pub major: u64,
+    pub minor: u64,
+    pub patch: u64,
+}
+
+impl Version {
+    pub fn new(major: u64, minor: u64, patch: u64) -> Version {
+        Version { major, minor, patch }
+    }
+
+    /// Parses the leading "major.minor[.patch]" part of a string, such as "2.30" or "1.8.0_292".
+    pub fn parse(s: &str) -> Option<Version> {
+        let numeric = s.split(|c: char| !c.is_ascii_digit() && c != '.').next().unwrap_or("");
+        let mut parts = numeric.split('.').map(|part| part.parse::<u64>());
+
+        let major = parts.next()?.ok()?;
+        let minor = parts.next()?.ok()?;
+        let patch = match parts.next() {
+            Some(Ok(patch)) => patch,
+            _ => 0,
+        };
+
+        Some(Version { major, minor, patch })
+    }
+}
+
+impl fmt::Display for Version {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}.{}.{}", self.major, self.minor, self.patch)
+    }
+}
+
+/// Finds the first version number in the output of a version command.
+///
+/// Words are stripped of their leading non digit characters, so "v20.5.0", "go1.21.0",
+/// "(v5.34.0)" and "OpenSSH_8.9p1" are found, and a version needs at least a major and a
+/// minor part, so years, build numbers and "(1)" are skipped.
+fn extract_version(output: &str) -> Option<Version> {
+    output.split_whitespace()
+        .map(|word| word.trim_start_matches(|c: char| !c.is_ascii_digit()))
+        .find_map(Version::parse)
+}
+
+/// Runs a program with stdin closed and LANG=C, and returns its stdout and stderr. The program
+/// is killed if it doesn't exit before the timeout.
+fn run_with_timeout(path: &Path, args: &[&str], timeout: Duration) -> Result<String, Error> {
+    let mut child = Command::new(path)
+        .args(args)
+        .env("LANG", "C")
+        .env("LC_ALL", "C")
+        .stdin(Stdio::null())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .map_err(Error::ExecFailed)?;
+
+    // the pipes are read in other threads, otherwise a program that writes a lot would block.
+    // They send what they read as it comes and an empty chunk at the end of the pipe.
+    let (sender, receiver) = mpsc::channel::<(usize, Vec<u8>)>();
+    let read_pipe = |index: usize, pipe: Option<Box<dyn Read + Send>>| {
+        let sender = sender.clone();
+        thread::spawn(move || {
+            let mut buffer = [0; 4096];
+            if let Some(mut pipe) = pipe {
+                loop {
+                    match pipe.read(&mut buffer) {
+                        Ok(0) | Err(_) => break,
+                        Ok(read) => if sender.send((index, buffer[..read].to_vec())).is_err() {
+                            return;
+                        },
+                    }
+                }
+            }
+            let _ = sender.send((index, vec![]));
+        });
+    };
+    read_pipe(0, child.stdout.take().map(|pipe| Box::new(pipe) as Box<dyn Read + Send>));
+    read_pipe(1, child.stderr.take().map(|pipe| Box::new(pipe) as Box<dyn Read + Send>));
+    drop(sender);
+
+    let deadline = Instant::now() + timeout;
+    loop {
+        if child.try_wait().map_err(Error::ExecFailed)?.is_some() {
+            break;
+        }
+
+        if Instant::now() >= deadline {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(Error::General(format!("'{} {}' did not exit in {:?}", path.display(), args.join(" "), timeout)));
+        }
+
+        thread::sleep(Duration::from_millis(10));
+    }
+
+    // a grandchild, such as a daemon that a launcher started, can keep the pipes open after the
+    // program exited, so the output is only collected until the deadline and the reader
+    // threads are left to end when the pipes close.
+    let mut outputs = [Vec::new(), Vec::new()];
+    let mut open_pipes = 2;
+    while open_pipes > 0 {
+        match receiver.recv_timeout(deadline.saturating_duration_since(Instant::now())) {
+            Ok((_, chunk)) if chunk.is_empty() => open_pipes -= 1,
+            Ok((index, chunk)) => outputs[index].extend_from_slice(&chunk),
+            Err(_) => break,
+        }
+    }
+
+    let mut output = String::from_utf8_lossy(&outputs[0]).into_owned();
+    output.push('\n');
+    output.push_str(&String::from_utf8_lossy(&outputs[1]));
+
+    Ok(output)
+}
+
+/// Get the version of an installed program, such as 2.39.2 for git. Works on both Windows And Linux.
+///
+/// The program is found with `find_executable()` and run with its known version argument, or
+/// with "--version", "-version", "-v" and "version" in order until one of the outputs has a
+/// version number in it. Every run has stdin closed, LANG=C and is killed after 5 seconds.
+///
+/// ```no_run
+/// use sys_info_extended::{program_version, Version};
+///
+/// let git = program_version("git").unwrap();
+/// assert!(git >= Version::new(2, 30, 0));
+/// ```
+pub fn program_version(program: &str) -> Result<Version, Error> {
+    program_version_with_timeout(program, VERSION_TIMEOUT)
+}
+
+/// Same as `program_version()` but with the given time limit for every run of the program.
+pub fn program_version_with_timeout(program: &str, timeout: Duration) -> Result<Version, Error> {
+    let path = find_executable(program)
+        .ok_or_else(|| Error::General(format!("'{}' is not found in the PATH", program)))?;
+
+    let name = path.file_stem().map(|stem| stem.to_string_lossy().to_lowercase()).unwrap_or_default();
+    let probes = match KNOWN_VERSION_ARGS.iter().find(|(known, _)| *known == name) {
+        Some((_, args)) => vec![*args],
+        None => VERSION_PROBES.to_vec(),
+    };
+
+    let mut last_error = None;
+    for args in probes {
+        match run_with_timeout(&path, args, timeout) {
+            Ok(output) => {
+                if let Some(version) = extract_version(&output) {
+                    return Ok(version);
+                }
+            },
+            Err(error) => last_error = Some(error),
+        }
+    }
+
+    Err(last_error.unwrap_or_else(|| Error::General(format!("cannot find a version number in the output of '{}'", program))))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_extract_version() {
+        let cases = [
+            ("git version 2.39.2", Version::new(2, 39, 2)),
+            ("Python 3.11", Version::new(3, 11, 0)),
+            ("v20.5.0", Version::new(20, 5, 0)),
+            ("go version go1.21.0 linux/amd64", Version::new(1, 21, 0)),
+            ("openjdk version \"1.8.0_292\" 2021-04-20", Version::new(1, 8, 0)),
+            ("This is perl 5, version 34, subversion 0 (v5.34.0) built for x86_64-linux", Version::new(5, 34, 0)),
+            ("OpenSSH_8.9p1 Ubuntu-3ubuntu0.1, OpenSSL 3.0.2 15 Mar 2022", Version::new(8, 9, 0)),
+            ("gcc (Ubuntu 11.4.0-1ubuntu1~22.04) 11.4.0", Version::new(11, 4, 0)),
+            ("GNU bash, version 5.1.16(1)-release (x86_64-pc-linux-gnu)", Version::new(5, 1, 16)),
+        ];
+
+        for (output, version) in cases.iter() {
+            assert_eq!(extract_version(output), Some(*version), "{}", output);
+        }
+
+        assert_eq!(extract_version("usage: tool [options]"), None);
+    }
+
+    #[test]
+    pub fn test_version_ordering() {
+        assert!(Version::parse("2.39.2").unwrap() >= Version::parse("2.30").unwrap());
+        assert!(Version::new(1, 10, 0) > Version::new(1, 9, 9));
+        assert_eq!(Version::new(3, 11, 0).to_string(), "3.11.0");
+    }
+
+    #[cfg(unix)]
+    #[test]
+    pub fn test_which_path() {
+        use std::fs;
+        use std::os::unix::fs::PermissionsExt;
+        use sysfs::TestDir;
+
+        let dir = TestDir::new("which");
+        dir.write(&[("bin/tool-1.0", "#!/bin/sh\n"), ("bin/notes", "")]);
+        fs::set_permissions(dir.join("bin/tool-1.0"), fs::Permissions::from_mode(0o755)).unwrap();
+        dir.symlink("tool-1.0", "bin/tool");
+
+        // the symlink is returned like a PATH hit would be, not the file it points to.
+        let tool = dir.join("bin/tool");
+        assert_eq!(which(tool.to_str().unwrap()), vec![tool]);
+        assert!(which(dir.join("bin/notes").to_str().unwrap()).is_empty());
+
+        let current_dir = env::current_dir().unwrap();
+        assert_eq!(absolute(Path::new("./bin/tool")), current_dir.join("bin").join("tool"));
+        assert_eq!(absolute(Path::new("../tool")), current_dir.join("..").join("tool"));
+    }
+
+    #[cfg(unix)]
+    #[test]
+    pub fn test_run_with_timeout() {
+        // the background sleep keeps the pipes open after sh exited.
+        let started = Instant::now();
+        let output = run_with_timeout(Path::new("/bin/sh"), &["-c", "echo 1.2.3; sleep 10 &"], Duration::from_secs(1)).unwrap();
+        assert!(started.elapsed() < Duration::from_secs(5));
+        assert_eq!(extract_version(&output), Some(Version::new(1, 2, 3)));
+
+        assert!(run_with_timeout(Path::new("/bin/sh"), &["-c", "sleep 10"], Duration::from_millis(100)).is_err());
+    }
+}
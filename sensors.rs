@@ -0,0 +1,269 @@
+//! Hardware sensors of hwmon and thermal zones, read from /sys/class on linux.
+
+use std::fs;
+use std::path::Path;
+
+use sysfs;
+use super::Error;
+
+/// What a hwmon sensor measures.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SensorKind {
+    Temperature,
+    Fan,
+    Voltage,
+    Current,
+    Power,
+}
+
+impl SensorKind {
+    /// The unit of the values of this kind of sensor.
+    pub fn unit(&self) -> &'static str {
+        match self {
+            SensorKind::Temperature => "°C",
+            SensorKind::Fan => "RPM",
+            SensorKind::Voltage => "V",
+            SensorKind::Current => "A",
+            SensorKind::Power => "W",
+        }
+    }
+
+    /// The file prefix in hwmon directories and the factor that turns the raw values into the unit.
+    fn prefix_and_scale(&self) -> (&'static str, f64) {
+        match self {
+            SensorKind::Temperature => ("temp", 1000.0),
+            SensorKind::Fan => ("fan", 1.0),
+            SensorKind::Voltage => ("in", 1000.0),
+            SensorKind::Current => ("curr", 1000.0),
+            SensorKind::Power => ("power", 1_000_000.0),
+        }
+    }
+}
+
+const SENSOR_KINDS: [SensorKind; 5] = [SensorKind::Temperature, SensorKind::Fan, SensorKind::Voltage, SensorKind::Current, SensorKind::Power];
+
+/// One sensor of a hwmon chip. The values are in the unit of its kind, see `SensorKind::unit()`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SensorReading {
+    /// The name of the hwmon chip, such as "coretemp", "k10temp", "nvme" or "amdgpu".
+    pub chip: String,
+    /// The label that the driver gives the sensor, such as "Package id 0" or "Tctl", otherwise
+    /// the file prefix of it, such as "temp1".
+    pub label: String,
+    pub kind: SensorKind,
+    /// None if the driver can't read the sensor right now, such as the sensors of a sleeping device.
+    pub value: Option<f64>,
+    pub min: Option<f64>,
+    pub max: Option<f64>,
+    pub critical: Option<f64>,
+}
+
+impl SensorReading {
+    /// Checks if the value reached the critical or the max threshold of the sensor.
+    pub fn is_over_limit(&self) -> bool {
+        match self.value {
+            Some(value) => self.critical.is_some_and(|critical| value >= critical) || self.max.is_some_and(|max| value >= max),
+            None => false,
+        }
+    }
+}
+
+/// A trip point of a thermal zone, the temperature where the kernel starts cooling or shuts down.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TripPoint {
+    /// "active" (fans), "passive" (throttling), "hot" or "critical" (shutdown).
+    pub kind: String,
+    /// In °C.
+    pub temperature: f64,
+    /// In °C, how far the temperature has to go under the trip point to end it.
+    pub hysteresis: Option<f64>,
+}
+
+/// A thermal zone of /sys/class/thermal.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ThermalZone {
+    /// N of thermal_zoneN.
+    pub zone: u32,
+    /// Such as "x86_pkg_temp", "acpitz" or "cpu-thermal".
+    pub zone_type: String,
+    /// In °C, None if it can't be read right now.
+    pub temperature: Option<f64>,
+    pub trip_points: Vec<TripPoint>,
+}
+
+impl ThermalZone {
+    /// The critical trip point of the zone, the kernel shuts the system down when it's reached.
+    pub fn critical_temperature(&self) -> Option<f64> {
+        self.trip_points.iter().find(|trip_point| trip_point.kind == "critical").map(|trip_point| trip_point.temperature)
+    }
+}
+
+/// Every sensor of the system.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct Sensors {
+    pub hwmon: Vec<SensorReading>,
+    pub thermal_zones: Vec<ThermalZone>,
+}
+
+impl Sensors {
+    /// The hwmon sensors of the given kind.
+    pub fn of_kind(&self, kind: SensorKind) -> Vec<&SensorReading> {
+        self.hwmon.iter().filter(|reading| reading.kind == kind).collect()
+    }
+}
+
+/// Get the temperature, fan, voltage, current and power sensors of /sys/class/hwmon and the
+/// thermal zones of /sys/class/thermal with their trip points, only for linux.
+///
+/// Virtual machines and containers often have none of them, so empty lists aren't an error.
+pub fn sensors() -> Result<Sensors, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    let mut sensors = Sensors::default();
+
+    for (_, dir) in sysfs::numbered_dirs("/sys/class/hwmon", "hwmon") {
+        sensors.hwmon.extend(read_hwmon_chip(&dir));
+    }
+
+    for (zone, dir) in sysfs::numbered_dirs("/sys/class/thermal", "thermal_zone") {
+        sensors.thermal_zones.push(read_thermal_zone(zone, &dir));
+    }
+
+    Ok(sensors)
+}
+
+fn read_scaled(dir: &Path, file: &str, scale: f64) -> Option<f64> {
+    sysfs::read_string(dir.join(file))?.parse::<i64>().ok().map(|value| value as f64 / scale)
+}
+
+/// Reads the sensors of a hwmon chip directory, every sensor has a "<prefix>N_input" file
+/// (power sensors may have "powerN_average" instead), see
+/// https://docs.kernel.org/hwmon/sysfs-interface.html.
+fn read_hwmon_chip(dir: &Path) -> Vec<SensorReading> {
+    let chip = sysfs::read_string(dir.join("name")).unwrap_or_default();
+    let files = match fs::read_dir(dir) {
+        Ok(entries) => entries.flatten().map(|entry| entry.file_name().to_string_lossy().into_owned()).collect::<Vec<String>>(),
+        Err(_) => return vec![],
+    };
+
+    let mut readings = vec![];
+
+    for kind in SENSOR_KINDS.iter() {
+        let (prefix, scale) = kind.prefix_and_scale();
+
+        let mut indexes = files.iter().filter_map(|file| {
+            let rest = file.strip_prefix(prefix)?;
+            let (index, suffix) = rest.split_once('_')?;
+            if suffix == "input" || (*kind == SensorKind::Power && suffix == "average") {
+                index.parse::<u32>().ok()
+            } else {
+                None
+            }
+        }).collect::<Vec<u32>>();
+        indexes.sort_unstable();
+        indexes.dedup();
+
+        for index in indexes {
+            let name = format!("{}{}", prefix, index);
+            let value = |suffix: &str| read_scaled(dir, &format!("{}_{}", name, suffix), scale);
+
+            readings.push(SensorReading {
+                chip: chip.clone(),
+                label: sysfs::read_string(dir.join(format!("{}_label", name))).unwrap_or_else(|| name.clone()),
+                kind: *kind,
+                value: value("input").or_else(|| value("average")),
+                min: value("min"),
+                max: value("max").or_else(|| value("cap")),
+                critical: value("crit"),
+            });
+        }
+    }
+
+    readings
+}
+
+fn read_thermal_zone(zone: u32, dir: &Path) -> ThermalZone {
+    let mut trip_points = vec![];
+
+    for trip in 0.. {
+        let kind = match sysfs::read_string(dir.join(format!("trip_point_{}_type", trip))) {
+            Some(kind) => kind,
+            None => break,
+        };
+
+        if let Some(temperature) = read_scaled(dir, &format!("trip_point_{}_temp", trip), 1000.0) {
+            trip_points.push(TripPoint {
+                kind,
+                temperature,
+                hysteresis: read_scaled(dir, &format!("trip_point_{}_hyst", trip), 1000.0),
+            });
+        }
+    }
+
+    ThermalZone {
+        zone,
+        zone_type: sysfs::read_string(dir.join("type")).unwrap_or_default(),
+        temperature: read_scaled(dir, "temp", 1000.0),
+        trip_points,
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use sysfs::TestDir;
+
+    #[test]
+    pub fn test_read_hwmon_chip_and_thermal_zone() {
+        let dir = TestDir::new("sensors");
+        dir.write(&[
+            ("hwmon0/name", "coretemp"),
+            ("hwmon0/temp1_input", "105000"),
+            ("hwmon0/temp1_label", "Package id 0"),
+            ("hwmon0/temp1_max", "100000"),
+            ("hwmon0/temp1_crit", "110000"),
+            ("hwmon0/temp2_input", "-5500"),
+            ("hwmon0/fan1_input", "1200"),
+            ("hwmon0/fan1_min", "300"),
+            ("hwmon0/in0_input", "1185"),
+            ("hwmon0/power1_average", "15250000"),
+            ("hwmon0/power1_cap", "45000000"),
+            ("thermal_zone0/type", "acpitz"),
+            ("thermal_zone0/temp", "47000"),
+            ("thermal_zone0/trip_point_0_type", "passive"),
+            ("thermal_zone0/trip_point_0_temp", "95000"),
+            ("thermal_zone0/trip_point_0_hyst", "2000"),
+            ("thermal_zone0/trip_point_1_type", "critical"),
+            ("thermal_zone0/trip_point_1_temp", "105000"),
+        ]);
+
+        let readings = read_hwmon_chip(&dir.join("hwmon0"));
+        let thermal_zone = read_thermal_zone(0, &dir.join("thermal_zone0"));
+
+        assert_eq!(readings.len(), 5);
+        assert_eq!(readings[0], SensorReading {
+            chip: "coretemp".to_string(),
+            label: "Package id 0".to_string(),
+            kind: SensorKind::Temperature,
+            value: Some(105.0),
+            min: None,
+            max: Some(100.0),
+            critical: Some(110.0),
+        });
+        assert!(readings[0].is_over_limit());
+        assert_eq!(readings[1].label, "temp2");
+        assert_eq!(readings[1].value, Some(-5.5));
+        assert!(!readings[1].is_over_limit());
+        assert_eq!((readings[2].kind, readings[2].value, readings[2].min), (SensorKind::Fan, Some(1200.0), Some(300.0)));
+        assert_eq!((readings[3].kind, readings[3].value), (SensorKind::Voltage, Some(1.185)));
+        assert_eq!((readings[4].kind, readings[4].value, readings[4].max), (SensorKind::Power, Some(15.25), Some(45.0)));
+
+        assert_eq!(thermal_zone.zone_type, "acpitz");
+        assert_eq!(thermal_zone.temperature, Some(47.0));
+        assert_eq!(thermal_zone.trip_points.len(), 2);
+        assert_eq!(thermal_zone.trip_points[0], TripPoint { kind: "passive".to_string(), temperature: 95.0, hysteresis: Some(2.0) });
+        assert_eq!(thermal_zone.critical_temperature(), Some(105.0));
+    }
+}
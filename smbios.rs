@@ -0,0 +1,343 @@
+//! A decoder of the SMBIOS structure table that linux exposes in /sys/firmware/dmi/tables/DMI.
+
+use std::fmt;
+use std::fs;
+
+use super::Error;
+
+const DMI_TABLE: &str = "/sys/firmware/dmi/tables/DMI";
+
+const TYPE_MEMORY_DEVICE: u8 = 17;
+const TYPE_END_OF_TABLE: u8 = 127;
+
+/// The type of a memory device, the "Memory Type" field of SMBIOS type 17.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoryType {
+    Dram,
+    Sdram,
+    Ddr,
+    Ddr2,
+    Ddr2FbDimm,
+    Ddr3,
+    Ddr4,
+    Ddr5,
+    Lpddr,
+    Lpddr2,
+    Lpddr3,
+    Lpddr4,
+    Lpddr5,
+    Hbm,
+    Hbm2,
+    Hbm3,
+    /// A type this decoder doesn't name, with its SMBIOS number.
+    Other(u8),
+    Unknown,
+}
+
+impl MemoryType {
+    pub fn from_smbios(value: u8) -> MemoryType {
+        match value {
+            0x02 => MemoryType::Unknown,
+            0x03 => MemoryType::Dram,
+            0x0F => MemoryType::Sdram,
+            0x12 => MemoryType::Ddr,
+            0x13 => MemoryType::Ddr2,
+            0x14 => MemoryType::Ddr2FbDimm,
+            0x18 => MemoryType::Ddr3,
+            0x1A => MemoryType::Ddr4,
+            0x1B => MemoryType::Lpddr,
+            0x1C => MemoryType::Lpddr2,
+            0x1D => MemoryType::Lpddr3,
+            0x1E => MemoryType::Lpddr4,
+            0x20 => MemoryType::Hbm,
+            0x21 => MemoryType::Hbm2,
+            0x22 => MemoryType::Ddr5,
+            0x23 => MemoryType::Lpddr5,
+            0x24 => MemoryType::Hbm3,
+            value => MemoryType::Other(value),
+        }
+    }
+
+    /// The name of the memory type, such as "DDR4" or "LPDDR5".
+    pub fn name(&self) -> &'static str {
+        match self {
+            MemoryType::Dram => "DRAM",
+            MemoryType::Sdram => "SDRAM",
+            MemoryType::Ddr => "DDR",
+            MemoryType::Ddr2 => "DDR2",
+            MemoryType::Ddr2FbDimm => "DDR2 FB-DIMM",
+            MemoryType::Ddr3 => "DDR3",
+            MemoryType::Ddr4 => "DDR4",
+            MemoryType::Ddr5 => "DDR5",
+            MemoryType::Lpddr => "LPDDR",
+            MemoryType::Lpddr2 => "LPDDR2",
+            MemoryType::Lpddr3 => "LPDDR3",
+            MemoryType::Lpddr4 => "LPDDR4",
+            MemoryType::Lpddr5 => "LPDDR5",
+            MemoryType::Hbm => "HBM",
+            MemoryType::Hbm2 => "HBM2",
+            MemoryType::Hbm3 => "HBM3",
+            MemoryType::Other(_) => "Other",
+            MemoryType::Unknown => "Unknown",
+        }
+    }
+}
+
+impl fmt::Display for MemoryType {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str(self.name())
+    }
+}
+
+/// The physical form of a memory device, the "Form Factor" field of SMBIOS type 17.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum MemoryFormFactor {
+    Simm,
+    Dimm,
+    SoDimm,
+    Rimm,
+    FbDimm,
+    /// Soldered to the mainboard.
+    Chip,
+    Die,
+    Camm,
+    /// A form factor this decoder doesn't name, with its SMBIOS number.
+    Other(u8),
+    Unknown,
+}
+
+impl MemoryFormFactor {
+    pub fn from_smbios(value: u8) -> MemoryFormFactor {
+        match value {
+            0x02 => MemoryFormFactor::Unknown,
+            0x03 => MemoryFormFactor::Simm,
+            0x05 => MemoryFormFactor::Chip,
+            0x09 => MemoryFormFactor::Dimm,
+            0x0C => MemoryFormFactor::Rimm,
+            0x0D => MemoryFormFactor::SoDimm,
+            0x0F => MemoryFormFactor::FbDimm,
+            0x10 => MemoryFormFactor::Die,
+            0x11 => MemoryFormFactor::Camm,
+            value => MemoryFormFactor::Other(value),
+        }
+    }
+}
+
+/// A memory device (SMBIOS type 17), a memory slot or soldered memory.
+#[derive(Debug, Clone, PartialEq)]
+pub struct MemoryDevice {
+    /// SMBIOS handle of the structure.
+    pub handle: u16,
+    /// Size in bytes, None if the slot is empty or the size is unknown.
+    pub size: Option<u64>,
+    /// Maximum speed of the module in MT/s (what tools and vendors call "MHz").
+    pub speed: Option<u32>,
+    /// The speed the memory controller runs the module at in MT/s.
+    pub configured_speed: Option<u32>,
+    pub memory_type: MemoryType,
+    pub form_factor: MemoryFormFactor,
+    /// The label of the slot, such as "DIMM_A1" or "ChannelA-DIMM0".
+    pub locator: Option<String>,
+    pub bank_locator: Option<String>,
+    pub manufacturer: Option<String>,
+    pub serial_number: Option<String>,
+    pub part_number: Option<String>,
+    pub rank: Option<u8>,
+}
+
+impl MemoryDevice {
+    /// Checks if a module is installed, empty slots have no size.
+    pub fn is_installed(&self) -> bool {
+        self.size.is_some()
+    }
+}
+
+/// A structure of the SMBIOS table: its type, handle, formatted area (including the 4 byte
+/// header) and strings.
+struct Structure<'a> {
+    kind: u8,
+    handle: u16,
+    formatted: &'a [u8],
+    strings: Vec<&'a [u8]>,
+}
+
+impl<'a> Structure<'a> {
+    fn byte(&self, offset: usize) -> Option<u8> {
+        self.formatted.get(offset).copied()
+    }
+
+    fn word(&self, offset: usize) -> Option<u16> {
+        let bytes = self.formatted.get(offset..offset + 2)?;
+        Some(u16::from_le_bytes([bytes[0], bytes[1]]))
+    }
+
+    fn dword(&self, offset: usize) -> Option<u32> {
+        let bytes = self.formatted.get(offset..offset + 4)?;
+        Some(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    /// Reads a string field, which is a 1 based index into the strings, 0 means no string.
+    /// Blank strings and the usual placeholders are None.
+    fn string(&self, offset: usize) -> Option<String> {
+        let index = self.byte(offset)? as usize;
+        let value = String::from_utf8_lossy(self.strings.get(index.checked_sub(1)?)?).trim().to_string();
+
+        match value.as_str() {
+            "" | "Not Specified" | "Unknown" | "NO DIMM" | "To Be Filled By O.E.M." => None,
+            _ => Some(value),
+        }
+    }
+}
+
+/// Splits an SMBIOS table into its structures, until the end of table structure or the end of
+/// the data. Every structure is a formatted area of the length in its header, followed by a set
+/// of NUL terminated strings that ends with another NUL.
+fn parse_structures(table: &[u8]) -> Vec<Structure<'_>> {
+    let mut structures = vec![];
+    let mut offset = 0;
+
+    while offset + 4 <= table.len() {
+        let kind = table[offset];
+        let length = table[offset + 1] as usize;
+        let handle = u16::from_le_bytes([table[offset + 2], table[offset + 3]]);
+
+        if length < 4 || offset + length > table.len() {
+            break;
+        }
+
+        let formatted = &table[offset..offset + length];
+        let strings_start = offset + length;
+        let strings_end = match table[strings_start..].windows(2).position(|pair| pair == [0, 0]) {
+            Some(position) => strings_start + position,
+            None => break,
+        };
+
+        let strings = if strings_end == strings_start {
+            vec![]
+        } else {
+            table[strings_start..strings_end].split(|byte| *byte == 0).collect()
+        };
+
+        structures.push(Structure { kind, handle, formatted, strings });
+
+        if kind == TYPE_END_OF_TABLE {
+            break;
+        }
+
+        offset = strings_end + 2;
+    }
+
+    structures
+}
+
+/// Decodes a type 17 structure, see the "Memory Device" section of the SMBIOS specification.
+/// Older versions have shorter structures, the fields they don't have are None.
+fn decode_memory_device(structure: &Structure) -> MemoryDevice {
+    // bit 15 of the size means kilobytes instead of megabytes, 0x7FFF means the size is in the
+    // extended size field, 0 is an empty slot and 0xFFFF an unknown size.
+    let size = match structure.word(0x0C) {
+        None | Some(0) | Some(0xFFFF) => None,
+        Some(0x7FFF) => structure.dword(0x1C).map(|megabytes| (megabytes & 0x7FFF_FFFF) as u64 * 1024 * 1024),
+        Some(size) if size & 0x8000 != 0 => Some((size & 0x7FFF) as u64 * 1024),
+        Some(size) => Some(size as u64 * 1024 * 1024),
+    };
+
+    // 0xFFFF means the speed is in the extended field of SMBIOS 3.3, 0 is unknown.
+    let speed = |offset: usize, extended_offset: usize| match structure.word(offset) {
+        None | Some(0) => None,
+        Some(0xFFFF) => structure.dword(extended_offset).map(|speed| speed & 0x7FFF_FFFF).filter(|speed| *speed != 0),
+        Some(speed) => Some(speed as u32),
+    };
+
+    MemoryDevice {
+        handle: structure.handle,
+        size,
+        speed: speed(0x15, 0x54),
+        configured_speed: speed(0x20, 0x58),
+        memory_type: structure.byte(0x12).map(MemoryType::from_smbios).unwrap_or(MemoryType::Unknown),
+        form_factor: structure.byte(0x0E).map(MemoryFormFactor::from_smbios).unwrap_or(MemoryFormFactor::Unknown),
+        locator: structure.string(0x10),
+        bank_locator: structure.string(0x11),
+        manufacturer: structure.string(0x17),
+        serial_number: structure.string(0x18),
+        part_number: structure.string(0x1A),
+        rank: structure.byte(0x1B).map(|attributes| attributes & 0x0F).filter(|rank| *rank != 0),
+    }
+}
+
+fn parse_memory_devices(table: &[u8]) -> Vec<MemoryDevice> {
+    parse_structures(table).iter()
+        .filter(|structure| structure.kind == TYPE_MEMORY_DEVICE)
+        .map(decode_memory_device)
+        .collect()
+}
+
+/// Get every memory device (slot or soldered memory) from the SMBIOS table, with its size,
+/// speed, type (DDR4, DDR5, LPDDR5...), form factor, slot and part information, only for linux.
+///
+/// The table in /sys/firmware/dmi/tables/DMI is only readable by root. Empty slots are listed
+/// too, see `MemoryDevice::is_installed()`.
+pub fn memory_devices() -> Result<Vec<MemoryDevice>, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    Ok(parse_memory_devices(&fs::read(DMI_TABLE)?))
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_parse_memory_devices_desktop() {
+        // an SMBIOS 3.3 table with a BIOS, a memory array, two DDR4/DDR5 modules, an empty slot
+        // and the end of table structure.
+        let devices = parse_memory_devices(include_bytes!("fixtures/smbios_desktop.bin"));
+
+        assert_eq!(devices.len(), 3);
+        assert_eq!(devices[0], MemoryDevice {
+            handle: 0x30,
+            size: Some(16 * 1024 * 1024 * 1024),
+            speed: Some(3200),
+            configured_speed: Some(2933),
+            memory_type: MemoryType::Ddr4,
+            form_factor: MemoryFormFactor::Dimm,
+            locator: Some("DIMM_A1".to_string()),
+            bank_locator: Some("BANK 0".to_string()),
+            manufacturer: Some("Kingston".to_string()),
+            serial_number: Some("1A2B3C4D".to_string()),
+            part_number: Some("KF432C16BB/16".to_string()),
+            rank: Some(2),
+        });
+
+        assert!(!devices[1].is_installed());
+        assert_eq!(devices[1].locator.as_deref(), Some("DIMM_A2"));
+        assert_eq!(devices[1].manufacturer, None);
+
+        // sizes and speeds that don't fit the old fields are in the extended ones.
+        assert_eq!(devices[2].size, Some(64 * 1024 * 1024 * 1024));
+        assert_eq!(devices[2].memory_type, MemoryType::Ddr5);
+        assert_eq!((devices[2].speed, devices[2].configured_speed), (Some(70000), Some(68000)));
+    }
+
+    #[test]
+    pub fn test_parse_memory_devices_laptop() {
+        // an SMBIOS 2.7 table with soldered LPDDR3 and an older, shorter type 17 structure.
+        let devices = parse_memory_devices(include_bytes!("fixtures/smbios_laptop.bin"));
+
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].memory_type.to_string(), "LPDDR3");
+        assert_eq!(devices[0].form_factor, MemoryFormFactor::Other(0x0B));
+        assert_eq!(devices[0].speed, Some(1867));
+        assert_eq!(devices[0].serial_number, None);
+        assert_eq!(devices[0].part_number.as_deref(), Some("MT52L1G32D4PG-107"));
+
+        assert_eq!(devices[1].size, Some(512 * 1024));
+        assert_eq!(devices[1].form_factor, MemoryFormFactor::Chip);
+        assert_eq!((devices[1].configured_speed, devices[1].rank), (None, None));
+        assert_eq!(devices[1].manufacturer.as_deref(), Some("Hynix"));
+
+        assert!(parse_memory_devices(&[17, 2, 0]).is_empty());
+    }
+}
@@ -0,0 +1,119 @@
+//! Small helpers for reading the one value files of sysfs and procfs.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+/// Reads a file and trims the trailing newline. Missing or unreadable files yield `None`.
+pub(crate) fn read_string<P: AsRef<Path>>(path: P) -> Option<String> {
+    fs::read_to_string(path).ok().map(|s| s.trim().to_string())
+}
+
+/// Reads a file that holds a single unsigned integer.
+pub(crate) fn read_u64<P: AsRef<Path>>(path: P) -> Option<u64> {
+    read_string(path).and_then(|s| s.parse::<u64>().ok())
+}
+
+/// Reads the name of the file that a symlink points to, such as "nvme" for the "driver" link of
+/// a device directory to ../../bus/pci/drivers/nvme.
+pub(crate) fn link_name<P: AsRef<Path>>(path: P) -> Option<String> {
+    fs::read_link(path).ok()?.file_name().map(|name| name.to_string_lossy().into_owned())
+}
+
+/// Lists the "cpuN" directories of /sys/devices/system/cpu, sorted by N.
+pub(crate) fn cpu_dirs() -> Vec<(u32, PathBuf)> {
+    numbered_dirs("/sys/devices/system/cpu", "cpu")
+}
+
+/// Lists the "<prefix>N" entries of a directory, such as the "hwmonN" ones of /sys/class/hwmon, sorted by N.
+pub(crate) fn numbered_dirs<P: AsRef<Path>>(dir: P, prefix: &str) -> Vec<(u32, PathBuf)> {
+    let mut dirs = vec![];
+
+    if let Ok(entries) = fs::read_dir(dir) {
+        for entry in entries.flatten() {
+            let name = entry.file_name();
+            let name = name.to_string_lossy();
+
+            if let Some(Ok(number)) = name.strip_prefix(prefix).map(|number| number.parse::<u32>()) {
+                dirs.push((number, entry.path()));
+            }
+        }
+    }
+
+    dirs.sort_by_key(|(number, _)| *number);
+    dirs
+}
+
+/// Parses a cpu list such as "0-3,8,10-11", like the ones in /sys/devices/system/cpu/online and cpuset files.
+pub(crate) fn parse_cpu_list(s: &str) -> Vec<u32> {
+    let mut cpus = vec![];
+
+    for range in s.trim().split(',').filter(|range| !range.is_empty()) {
+        let mut bounds = range.splitn(2, '-').map(|bound| bound.trim().parse::<u32>());
+
+        match (bounds.next(), bounds.next()) {
+            (Some(Ok(start)), Some(Ok(end))) => cpus.extend(start..=end),
+            (Some(Ok(cpu)), None) => cpus.push(cpu),
+            _ => (),
+        }
+    }
+
+    cpus
+}
+
+/// A directory tree of sysfs like files for tests, removed when it's dropped, so a failing
+/// test doesn't leave it behind.
+#[cfg(test)]
+pub(crate) struct TestDir {
+    path: PathBuf,
+}
+
+#[cfg(test)]
+impl TestDir {
+    /// Creates an empty "sys-info-<name>-test-<pid>" directory in the temp directory.
+    pub(crate) fn new(name: &str) -> TestDir {
+        let path = std::env::temp_dir().join(format!("sys-info-{}-test-{}", name, std::process::id()));
+        let _ = fs::remove_dir_all(&path);
+        fs::create_dir_all(&path).unwrap();
+
+        TestDir { path }
+    }
+
+    pub(crate) fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// The path of a file or directory in the tree.
+    pub(crate) fn join<P: AsRef<Path>>(&self, path: P) -> PathBuf {
+        self.path.join(path)
+    }
+
+    /// Creates the given directories of the tree.
+    pub(crate) fn create_dirs(&self, dirs: &[&str]) {
+        for dir in dirs {
+            fs::create_dir_all(self.join(dir)).unwrap();
+        }
+    }
+
+    /// Writes the given files of the tree with a trailing newline like sysfs has, their
+    /// directories are created if needed.
+    pub(crate) fn write(&self, files: &[(&str, &str)]) {
+        for (file, content) in files {
+            let path = self.join(file);
+            fs::create_dir_all(path.parent().unwrap()).unwrap();
+            fs::write(path, format!("{}\n", content)).unwrap();
+        }
+    }
+
+    /// Creates a symlink of the tree, like the "driver" links of devices.
+    #[cfg(unix)]
+    pub(crate) fn symlink<P: AsRef<Path>>(&self, target: P, link: &str) {
+        std::os::unix::fs::symlink(target, self.join(link)).unwrap();
+    }
+}
+
+#[cfg(test)]
+impl Drop for TestDir {
+    fn drop(&mut self) {
+        let _ = fs::remove_dir_all(&self.path);
+    }
+}
@@ -0,0 +1,253 @@
+//! USB devices of /sys/bus/usb/devices on linux, with names from the usb.ids database.
+
+use std::fs;
+use std::io;
+use std::path::Path;
+
+use ids::IdsDatabase;
+use sysfs;
+use super::Error;
+
+const USB_IDS: [&str; 3] = ["/usr/share/hwdata/usb.ids", "/usr/share/misc/usb.ids", "/var/lib/usbutils/usb.ids"];
+
+/// An interface of a USB device, the part of it that a driver binds to.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UsbInterface {
+    pub number: u8,
+    /// Such as 0x03 for HID, 0x08 for mass storage or 0x0e for video.
+    pub class: u8,
+    pub subclass: u8,
+    pub protocol: u8,
+    /// The kernel driver bound to the interface, such as "usbhid" or "uvcvideo".
+    pub driver: Option<String>,
+    /// The interface string of the device, if it has one.
+    pub description: Option<String>,
+    /// The name of the class from the usb.ids database.
+    pub class_name: Option<String>,
+}
+
+/// A device of the USB bus, root hubs included. The names of the usb.ids database are None
+/// when it isn't installed or doesn't know the ids.
+#[derive(Debug, Clone, PartialEq)]
+pub struct UsbDevice {
+    pub bus: u32,
+    /// The address of the device on its bus.
+    pub device_number: u32,
+    /// The bus and the port path of the device, such as "1-2.3" for port 3 of the hub on port 2
+    /// of bus 1, or "usb1" for the root hub of bus 1.
+    pub port_path: String,
+    pub vendor_id: u16,
+    pub product_id: u16,
+    /// The manufacturer, product and serial number strings that the device reports.
+    pub manufacturer: Option<String>,
+    pub product: Option<String>,
+    pub serial_number: Option<String>,
+    /// The device class, 0 means that every interface has its own class.
+    pub class: u8,
+    pub subclass: u8,
+    pub protocol: u8,
+    /// The USB version that the device supports, such as "2.00" or "3.20".
+    pub usb_version: Option<String>,
+    /// The negotiated speed in Mbit/s, such as 12, 480 or 5000.
+    pub speed: Option<f64>,
+    /// The power that the active configuration draws from the bus in mA.
+    pub max_power: Option<u32>,
+    pub interfaces: Vec<UsbInterface>,
+    pub vendor_name: Option<String>,
+    pub product_name: Option<String>,
+    pub class_name: Option<String>,
+}
+
+/// Get every device of the USB buses with its ids, strings, class, speed, configured power
+/// and interfaces with their drivers, only for linux. The names are looked up in the usb.ids
+/// database of /usr/share/hwdata, /usr/share/misc or /var/lib/usbutils if one is installed.
+pub fn usb_devices() -> Result<Vec<UsbDevice>, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    read_usb_devices(Path::new("/sys/bus/usb/devices"), IdsDatabase::load(&USB_IDS).as_ref())
+}
+
+/// Reads the devices of a directory like /sys/bus/usb/devices, which lists the interfaces too,
+/// as "<port path>:<configuration>.<interface>" entries.
+fn read_usb_devices(dir: &Path, database: Option<&IdsDatabase>) -> Result<Vec<UsbDevice>, Error> {
+    let mut devices = match fs::read_dir(dir) {
+        Ok(entries) => entries.flatten()
+            .filter(|entry| !entry.file_name().to_string_lossy().contains(':'))
+            .filter_map(|entry| read_usb_device(&entry.path(), database))
+            .collect::<Vec<UsbDevice>>(),
+        Err(ref error) if error.kind() == io::ErrorKind::NotFound => vec![],
+        Err(error) => return Err(Error::IO(error)),
+    };
+
+    devices.sort_by_key(|device| (device.bus, device.device_number));
+    Ok(devices)
+}
+
+fn read_hex<P: AsRef<Path>>(path: P) -> Option<u16> {
+    u16::from_str_radix(&sysfs::read_string(path)?, 16).ok()
+}
+
+/// Reads a device directory, see https://www.kernel.org/doc/Documentation/ABI/stable/sysfs-bus-usb.
+/// The ids and classes are hex numbers without a "0x" prefix there.
+fn read_usb_device(dir: &Path, database: Option<&IdsDatabase>) -> Option<UsbDevice> {
+    let string = |file: &str| sysfs::read_string(dir.join(file)).filter(|value| !value.is_empty());
+    let hex = |file: &str| read_hex(dir.join(file));
+
+    let vendor_id = hex("idVendor")?;
+    let product_id = hex("idProduct")?;
+    let (class, subclass, protocol) = (hex("bDeviceClass").unwrap_or(0) as u8, hex("bDeviceSubClass").unwrap_or(0) as u8, hex("bDeviceProtocol").unwrap_or(0) as u8);
+
+    let mut interfaces = match fs::read_dir(dir) {
+        Ok(entries) => entries.flatten()
+            .filter(|entry| entry.file_name().to_string_lossy().contains(':'))
+            .filter_map(|entry| read_usb_interface(&entry.path(), database))
+            .collect::<Vec<UsbInterface>>(),
+        Err(_) => vec![],
+    };
+    interfaces.sort_by_key(|interface| interface.number);
+
+    Some(UsbDevice {
+        bus: string("busnum")?.parse::<u32>().ok()?,
+        device_number: string("devnum")?.parse::<u32>().ok()?,
+        port_path: dir.file_name()?.to_string_lossy().into_owned(),
+        vendor_id,
+        product_id,
+        manufacturer: string("manufacturer"),
+        product: string("product"),
+        serial_number: string("serial"),
+        class,
+        subclass,
+        protocol,
+        usb_version: string("version"),
+        speed: string("speed").and_then(|speed| speed.parse::<f64>().ok()),
+        // such as "500mA", empty if the device isn't configured.
+        max_power: string("bMaxPower").and_then(|power| power.trim_end_matches("mA").parse::<u32>().ok()),
+        interfaces,
+        vendor_name: database.and_then(|database| database.vendor(vendor_id)).map(String::from),
+        product_name: database.and_then(|database| database.device(vendor_id, product_id)).map(String::from),
+        class_name: database.and_then(|database| database.class(class, subclass, protocol)).map(String::from),
+    })
+}
+
+fn read_usb_interface(dir: &Path, database: Option<&IdsDatabase>) -> Option<UsbInterface> {
+    let hex = |file: &str| read_hex(dir.join(file)).map(|value| value as u8);
+    let (class, subclass, protocol) = (hex("bInterfaceClass")?, hex("bInterfaceSubClass").unwrap_or(0), hex("bInterfaceProtocol").unwrap_or(0));
+
+    Some(UsbInterface {
+        number: hex("bInterfaceNumber")?,
+        class,
+        subclass,
+        protocol,
+        driver: sysfs::link_name(dir.join("driver")),
+        description: sysfs::read_string(dir.join("interface")).filter(|description| !description.is_empty()),
+        class_name: database.and_then(|database| database.class(class, subclass, protocol)).map(String::from),
+    })
+}
+
+// the test links the drivers of the interfaces like sysfs does, with unix symlinks.
+#[cfg(all(test, unix))]
+mod test {
+    use super::*;
+    use sysfs::TestDir;
+
+    #[test]
+    pub fn test_read_usb_devices() {
+        let dir = TestDir::new("usb");
+        dir.write(&[
+            ("devices/usb1/busnum", "1"),
+            ("devices/usb1/devnum", "1"),
+            ("devices/usb1/idVendor", "1d6b"),
+            ("devices/usb1/idProduct", "0002"),
+            ("devices/usb1/bDeviceClass", "09"),
+            ("devices/usb1/speed", "480"),
+            ("devices/usb1/bMaxPower", "0mA"),
+            ("devices/1-2.3/busnum", "1"),
+            ("devices/1-2.3/devnum", "5"),
+            ("devices/1-2.3/idVendor", "046d"),
+            ("devices/1-2.3/idProduct", "085c"),
+            ("devices/1-2.3/manufacturer", ""),
+            ("devices/1-2.3/product", "C922 Pro Stream Webcam"),
+            ("devices/1-2.3/serial", "4A2F5E1D"),
+            ("devices/1-2.3/bDeviceClass", "ef"),
+            ("devices/1-2.3/bDeviceSubClass", "02"),
+            ("devices/1-2.3/bDeviceProtocol", "01"),
+            ("devices/1-2.3/version", " 2.00"),
+            ("devices/1-2.3/speed", "480"),
+            ("devices/1-2.3/bMaxPower", "500mA"),
+            ("devices/1-2.3/1-2.3:1.0/bInterfaceNumber", "00"),
+            ("devices/1-2.3/1-2.3:1.0/bInterfaceClass", "0e"),
+            ("devices/1-2.3/1-2.3:1.0/bInterfaceSubClass", "01"),
+            ("devices/1-2.3/1-2.3:1.0/bInterfaceProtocol", "00"),
+            ("devices/1-2.3/1-2.3:1.2/bInterfaceNumber", "02"),
+            ("devices/1-2.3/1-2.3:1.2/bInterfaceClass", "01"),
+            ("devices/1-2.3/1-2.3:1.2/bInterfaceSubClass", "01"),
+            ("devices/1-2.3/1-2.3:1.2/bInterfaceProtocol", "00"),
+            ("devices/1-2.3/1-2.3:1.2/interface", "C922 Audio"),
+        ]);
+        dir.create_dirs(&["drivers/uvcvideo"]);
+        dir.symlink("../../../drivers/uvcvideo", "devices/1-2.3/1-2.3:1.0/driver");
+        dir.symlink(dir.join("devices/1-2.3/1-2.3:1.0"), "devices/1-2.3:1.0");
+
+        let database = IdsDatabase::parse("\
+046d  Logitech, Inc.
+\t085c  C922 Pro Stream Webcam
+1d6b  Linux Foundation
+\t0002  2.0 root hub
+C 09  Hub
+\t00  Unused
+\t\t00  Full speed (or root) hub
+C 0e  Video
+\t01  Video Control
+");
+
+        let devices = read_usb_devices(&dir.join("devices"), Some(&database)).unwrap();
+
+        assert_eq!(devices.len(), 2);
+        assert_eq!(devices[0].port_path, "usb1");
+        assert_eq!(devices[0].product_name.as_deref(), Some("2.0 root hub"));
+        assert_eq!(devices[0].class_name.as_deref(), Some("Full speed (or root) hub"));
+        assert_eq!((devices[0].max_power, devices[0].interfaces.len()), (Some(0), 0));
+
+        assert_eq!(devices[1], UsbDevice {
+            bus: 1,
+            device_number: 5,
+            port_path: "1-2.3".to_string(),
+            vendor_id: 0x046d,
+            product_id: 0x085c,
+            manufacturer: None,
+            product: Some("C922 Pro Stream Webcam".to_string()),
+            serial_number: Some("4A2F5E1D".to_string()),
+            class: 0xef,
+            subclass: 0x02,
+            protocol: 0x01,
+            usb_version: Some("2.00".to_string()),
+            speed: Some(480.0),
+            max_power: Some(500),
+            interfaces: vec![
+                UsbInterface {
+                    number: 0,
+                    class: 0x0e,
+                    subclass: 0x01,
+                    protocol: 0x00,
+                    driver: Some("uvcvideo".to_string()),
+                    description: None,
+                    class_name: Some("Video Control".to_string()),
+                },
+                UsbInterface {
+                    number: 2,
+                    class: 0x01,
+                    subclass: 0x01,
+                    protocol: 0x00,
+                    driver: None,
+                    description: Some("C922 Audio".to_string()),
+                    class_name: None,
+                },
+            ],
+            vendor_name: Some("Logitech, Inc.".to_string()),
+            product_name: Some("C922 Pro Stream Webcam".to_string()),
+            class_name: None,
+        });
+    }
+}
@@ -0,0 +1,340 @@
+//! Detection of hypervisors and container runtimes on linux, similar to systemd-detect-virt.
+
+use std::env;
+use std::fs;
+use std::path::Path;
+
+use sysfs;
+use super::Error;
+
+/// A hypervisor that runs the system as a virtual machine.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Hypervisor {
+    Kvm,
+    Qemu,
+    Vmware,
+    HyperV,
+    Xen,
+    VirtualBox,
+    Firecracker,
+    /// Another hypervisor, such as "amazon", "google", "parallels" or "bhyve", or the raw
+    /// cpuid vendor string of an unknown one.
+    Other(String),
+}
+
+/// A container runtime or environment that the system runs in.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Container {
+    Docker,
+    Podman,
+    Lxc,
+    SystemdNspawn,
+    Wsl,
+    /// A pod of Kubernetes, whatever its container runtime is.
+    Kubernetes,
+    /// Another container, the value of the "container" env that its manager sets.
+    Other(String),
+}
+
+/// Whether the system is a virtual machine, a container, both or neither (bare metal).
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Virtualization {
+    pub hypervisor: Option<Hypervisor>,
+    pub container: Option<Container>,
+}
+
+impl Virtualization {
+    pub fn is_virtual_machine(&self) -> bool {
+        self.hypervisor.is_some()
+    }
+
+    pub fn is_container(&self) -> bool {
+        self.container.is_some()
+    }
+
+    pub fn is_bare_metal(&self) -> bool {
+        self.hypervisor.is_none() && self.container.is_none()
+    }
+}
+
+/// Get the hypervisor and the container the system runs in, only for linux.
+///
+/// The hypervisor is detected from the ACPI tables of Firecracker, the DMI strings of
+/// /sys/class/dmi/id that only hypervisors set, /proc/xen, the hypervisor leaf of cpuid and
+/// the remaining DMI strings such as "QEMU", in that order. The container is
+/// detected from Kubernetes markers, /run/systemd/container, the "container" env of pid 1,
+/// /run/.containerenv, /.dockerenv, /proc/1/cgroup and the kernel release of WSL. Inside a
+/// container the hypervisor is the one of the host, since they share the kernel and cpu.
+pub fn virtualization() -> Result<Virtualization, Error> {
+    if !cfg!(target_os = "linux") {
+        return Err(Error::UnsupportedSystem);
+    }
+
+    Ok(Virtualization {
+        hypervisor: detect_hypervisor(),
+        container: detect_container(),
+    })
+}
+
+fn detect_hypervisor() -> Option<Hypervisor> {
+    let dmi = ["sys_vendor", "product_name", "board_vendor", "bios_vendor"].iter()
+        .filter_map(|file| sysfs::read_string(Path::new("/sys/class/dmi/id").join(file)))
+        .collect::<Vec<String>>();
+
+    // the ACPI tables are only readable by root.
+    let acpi_header = fs::read("/sys/firmware/acpi/tables/FACP").unwrap_or_default();
+
+    // in dom0 the system is the host of the other Xen domains.
+    let xen_guest = Path::new("/proc/xen").is_dir() && !sysfs::read_string("/proc/xen/capabilities").unwrap_or_default().contains("control_d");
+
+    detect_hypervisor_from(&dmi, &acpi_header, xen_guest, cpuid_hypervisor_vendor().as_deref())
+}
+
+/// Picks the hypervisor like systemd-detect-virt does. Only the DMI strings that name the
+/// hypervisor and not the emulated machine are trusted before cpuid, the others such as
+/// "QEMU" are the same for QEMU with and without KVM, which cpuid tells apart.
+fn detect_hypervisor_from(dmi: &[String], acpi_header: &[u8], xen_guest: bool, cpuid_vendor: Option<&str>) -> Option<Hypervisor> {
+    if let Some(hypervisor) = hypervisor_from_firmware(dmi, acpi_header) {
+        return Some(hypervisor);
+    }
+
+    if xen_guest {
+        return Some(Hypervisor::Xen);
+    }
+
+    match (cpuid_vendor.map(hypervisor_from_cpuid_vendor), hypervisor_from_dmi(dmi)) {
+        // an unknown cpuid vendor is less telling than a known DMI string.
+        (Some(Hypervisor::Other(_)), Some(hypervisor)) => Some(hypervisor),
+        (Some(hypervisor), _) => Some(hypervisor),
+        (None, hypervisor) => hypervisor,
+    }
+}
+
+/// Detects the hypervisors whose DMI strings or ACPI tables can be trusted before cpuid.
+/// Firecracker has no DMI and is only known from the "FIRECK" OEM id of its ACPI tables. Its
+/// "virtio_mmio.device=" kernel arguments aren't a sign of it, since QEMU microvm and
+/// cloud-hypervisor pass them too, these fall through to cpuid and are reported as KVM.
+fn hypervisor_from_firmware(dmi: &[String], acpi_header: &[u8]) -> Option<Hypervisor> {
+    if acpi_header.get(10..16) == Some(b"FIRECK") {
+        return Some(Hypervisor::Firecracker);
+    }
+
+    // Oracle (VirtualBox), Xen, Amazon, Google and Parallels are the ones systemd trusts.
+    hypervisor_from_dmi(dmi).filter(|hypervisor| match hypervisor {
+        Hypervisor::Firecracker | Hypervisor::VirtualBox | Hypervisor::Xen => true,
+        Hypervisor::Other(name) => name == "amazon" || name == "google" || name == "parallels",
+        _ => false,
+    })
+}
+
+/// Matches the vendor and product strings of DMI against the ones that hypervisors set.
+fn hypervisor_from_dmi(dmi: &[String]) -> Option<Hypervisor> {
+    let has = |needle: &str| dmi.iter().any(|value| value.contains(needle));
+
+    if has("Firecracker") {
+        Some(Hypervisor::Firecracker)
+    } else if has("Microsoft Corporation") && has("Virtual Machine") {
+        Some(Hypervisor::HyperV)
+    } else if has("VirtualBox") || has("innotek GmbH") {
+        Some(Hypervisor::VirtualBox)
+    } else if has("VMware") || has("VMW") {
+        Some(Hypervisor::Vmware)
+    } else if has("QEMU") {
+        Some(Hypervisor::Qemu)
+    } else if has("KVM") {
+        Some(Hypervisor::Kvm)
+    } else if has("Xen") {
+        Some(Hypervisor::Xen)
+    } else if has("Amazon EC2") {
+        Some(Hypervisor::Other("amazon".to_string()))
+    } else if has("Google Compute Engine") {
+        Some(Hypervisor::Other("google".to_string()))
+    } else if has("Parallels") {
+        Some(Hypervisor::Other("parallels".to_string()))
+    } else if has("Bochs") {
+        Some(Hypervisor::Other("bochs".to_string()))
+    } else {
+        None
+    }
+}
+
+fn hypervisor_from_cpuid_vendor(vendor: &str) -> Hypervisor {
+    match vendor {
+        "KVMKVMKVM" => Hypervisor::Kvm,
+        "TCGTCGTCGTCG" => Hypervisor::Qemu,
+        "VMwareVMware" => Hypervisor::Vmware,
+        "Microsoft Hv" => Hypervisor::HyperV,
+        "XenVMMXenVMM" => Hypervisor::Xen,
+        "VBoxVBoxVBox" => Hypervisor::VirtualBox,
+        "prl hyperv  " | " lrpepyh  vr" => Hypervisor::Other("parallels".to_string()),
+        "bhyve bhyve " => Hypervisor::Other("bhyve".to_string()),
+        vendor => Hypervisor::Other(vendor.trim().to_string()),
+    }
+}
+
+/// Returns the vendor string of the hypervisor leaf of cpuid if the hypervisor bit is set.
+#[cfg(any(target_arch = "x86", target_arch = "x86_64"))]
+fn cpuid_hypervisor_vendor() -> Option<String> {
+    #[cfg(target_arch = "x86")]
+    use std::arch::x86::__cpuid;
+    #[cfg(target_arch = "x86_64")]
+    use std::arch::x86_64::__cpuid;
+
+    // __cpuid is a safe function on newer rust versions and unsafe on older ones.
+    #[allow(unused_unsafe)]
+    let (features, leaf) = unsafe { (__cpuid(1), __cpuid(0x4000_0000)) };
+
+    // every hypervisor sets bit 31 of ecx of leaf 1, bare metal cpus leave it zero.
+    if features.ecx & (1 << 31) == 0 {
+        return None;
+    }
+
+    let mut vendor = Vec::with_capacity(12);
+    for register in [leaf.ebx, leaf.ecx, leaf.edx].iter() {
+        vendor.extend_from_slice(&register.to_le_bytes());
+    }
+
+    Some(String::from_utf8_lossy(&vendor).trim_end_matches('\0').to_string())
+}
+
+/// Other architectures have no cpuid, their hypervisor is only detected from DMI and ACPI.
+#[cfg(not(any(target_arch = "x86", target_arch = "x86_64")))]
+fn cpuid_hypervisor_vendor() -> Option<String> {
+    None
+}
+
+fn detect_container() -> Option<Container> {
+    let pid1_cgroup = sysfs::read_string("/proc/1/cgroup").unwrap_or_default();
+
+    if env::var_os("KUBERNETES_SERVICE_HOST").is_some()
+        || Path::new("/var/run/secrets/kubernetes.io/serviceaccount").is_dir()
+        || pid1_cgroup.contains("kubepods") {
+        return Some(Container::Kubernetes);
+    }
+
+    // systemd writes the container it runs in to /run/systemd/container.
+    if let Some(name) = sysfs::read_string("/run/systemd/container").filter(|name| !name.is_empty()) {
+        return Some(container_from_name(&name));
+    }
+
+    // /proc/1/environ is only readable by root, the env of the current process is the next best.
+    let pid1_container = fs::read("/proc/1/environ").ok().and_then(|environ| {
+        environ.split(|byte| *byte == 0)
+            .find_map(|variable| variable.strip_prefix(b"container="))
+            .map(|name| String::from_utf8_lossy(name).into_owned())
+    });
+    if let Some(name) = pid1_container.or_else(|| env::var("container").ok()).filter(|name| !name.is_empty()) {
+        return Some(container_from_name(&name));
+    }
+
+    if Path::new("/run/.containerenv").exists() {
+        return Some(Container::Podman);
+    }
+
+    if Path::new("/.dockerenv").exists() {
+        return Some(Container::Docker);
+    }
+
+    if let Some(container) = container_from_cgroup(&pid1_cgroup) {
+        return Some(container);
+    }
+
+    let osrelease = sysfs::read_string("/proc/sys/kernel/osrelease").unwrap_or_default().to_lowercase();
+    if osrelease.contains("microsoft") || osrelease.contains("wsl") {
+        return Some(Container::Wsl);
+    }
+
+    None
+}
+
+/// Maps the value of the "container" env that container managers set.
+fn container_from_name(name: &str) -> Container {
+    match name {
+        "docker" => Container::Docker,
+        "podman" => Container::Podman,
+        "lxc" | "lxc-libvirt" => Container::Lxc,
+        "systemd-nspawn" => Container::SystemdNspawn,
+        "wsl" => Container::Wsl,
+        name => Container::Other(name.to_string()),
+    }
+}
+
+/// Looks for the group names of container runtimes in /proc/1/cgroup, only cgroup v1 and
+/// containers without a cgroup namespace show them.
+fn container_from_cgroup(cgroup: &str) -> Option<Container> {
+    if cgroup.contains("/docker/") || cgroup.contains("/docker-") {
+        Some(Container::Docker)
+    } else if cgroup.contains("libpod") {
+        Some(Container::Podman)
+    } else if cgroup.contains("/lxc/") || cgroup.contains("/lxc.payload") {
+        Some(Container::Lxc)
+    } else if cgroup.contains("/machine.slice/machine-") {
+        Some(Container::SystemdNspawn)
+    } else {
+        None
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    pub fn test_hypervisor_from_dmi() {
+        let dmi = |values: &[&str]| values.iter().map(|value| value.to_string()).collect::<Vec<String>>();
+
+        assert_eq!(hypervisor_from_dmi(&dmi(&["QEMU", "Standard PC (Q35 + ICH9, 2009)", "", "SeaBIOS"])), Some(Hypervisor::Qemu));
+        assert_eq!(hypervisor_from_dmi(&dmi(&["innotek GmbH", "VirtualBox", "Oracle Corporation"])), Some(Hypervisor::VirtualBox));
+        assert_eq!(hypervisor_from_dmi(&dmi(&["Microsoft Corporation", "Virtual Machine", "Microsoft Corporation"])), Some(Hypervisor::HyperV));
+        assert_eq!(hypervisor_from_dmi(&dmi(&["VMware, Inc.", "VMware Virtual Platform"])), Some(Hypervisor::Vmware));
+        assert_eq!(hypervisor_from_dmi(&dmi(&["Amazon EC2", "m5.large"])), Some(Hypervisor::Other("amazon".to_string())));
+        assert_eq!(hypervisor_from_dmi(&dmi(&["Microsoft Corporation", "Surface Laptop 4"])), None);
+        assert_eq!(hypervisor_from_dmi(&dmi(&["Dell Inc.", "PowerEdge R640"])), None);
+    }
+
+    #[test]
+    pub fn test_hypervisor_from_firmware() {
+        let header = |oem_id: &[u8]| [&b"FACP\x14\x01\x00\x00\x06\x00"[..], oem_id, b"MICROVM "].concat();
+
+        assert_eq!(hypervisor_from_firmware(&[], &header(b"FIRECK")), Some(Hypervisor::Firecracker));
+        assert_eq!(hypervisor_from_firmware(&["Firecracker".to_string()], &[]), Some(Hypervisor::Firecracker));
+        // QEMU microvm passes virtio_mmio.device= on the kernel command line like Firecracker,
+        // but its ACPI tables have the "BOCHS " OEM id.
+        assert_eq!(hypervisor_from_firmware(&[], &header(b"BOCHS ")), None);
+        assert_eq!(hypervisor_from_firmware(&["QEMU".to_string()], &[]), None);
+        assert_eq!(hypervisor_from_firmware(&[], &[]), None);
+    }
+
+    #[test]
+    pub fn test_detect_hypervisor_from() {
+        let qemu = ["QEMU".to_string(), "Standard PC (i440FX + PIIX, 1996)".to_string()];
+
+        // a KVM accelerated QEMU guest, such as the ones of Proxmox, OpenStack and libvirt.
+        assert_eq!(detect_hypervisor_from(&qemu, &[], false, Some("KVMKVMKVM")), Some(Hypervisor::Kvm));
+        assert_eq!(detect_hypervisor_from(&qemu, &[], false, Some("TCGTCGTCGTCG")), Some(Hypervisor::Qemu));
+        assert_eq!(detect_hypervisor_from(&qemu, &[], false, Some("")), Some(Hypervisor::Qemu));
+        assert_eq!(detect_hypervisor_from(&qemu, &[], false, None), Some(Hypervisor::Qemu));
+        // EC2 instances run on KVM (Nitro) or Xen, DMI tells that they are Amazon ones.
+        assert_eq!(detect_hypervisor_from(&["Amazon EC2".to_string()], &[], false, Some("KVMKVMKVM")), Some(Hypervisor::Other("amazon".to_string())));
+        assert_eq!(detect_hypervisor_from(&[], &[], true, Some("KVMKVMKVM")), Some(Hypervisor::Xen));
+        assert_eq!(detect_hypervisor_from(&[], &[], false, None), None);
+    }
+
+    #[test]
+    pub fn test_hypervisor_from_cpuid_vendor() {
+        assert_eq!(hypervisor_from_cpuid_vendor("KVMKVMKVM"), Hypervisor::Kvm);
+        assert_eq!(hypervisor_from_cpuid_vendor("Microsoft Hv"), Hypervisor::HyperV);
+        assert_eq!(hypervisor_from_cpuid_vendor("ACRNACRNACRN"), Hypervisor::Other("ACRNACRNACRN".to_string()));
+    }
+
+    #[test]
+    pub fn test_container_detection_helpers() {
+        assert_eq!(container_from_name("systemd-nspawn"), Container::SystemdNspawn);
+        assert_eq!(container_from_name("oci"), Container::Other("oci".to_string()));
+
+        assert_eq!(container_from_cgroup("12:memory:/docker/0123456789abcdef\n0::/docker/0123456789abcdef\n"), Some(Container::Docker));
+        assert_eq!(container_from_cgroup("0::/machine.slice/libpod-0123.scope\n"), Some(Container::Podman));
+        assert_eq!(container_from_cgroup("0::/lxc.payload.web/init.scope\n"), Some(Container::Lxc));
+        assert_eq!(container_from_cgroup("0::/init.scope\n"), None);
+    }
+}